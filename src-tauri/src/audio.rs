@@ -1,20 +1,29 @@
 use crate::clipboard;
 use crate::dictionary::{fix_transcription_with_dictionary, get_cc_rules_path, Dictionary};
 use crate::engine::{
-    engine::ParakeetEngine, engine::ParakeetModelParams, transcription_engine::TranscriptionEngine,
+    engine::ParakeetEngine, engine::ParakeetModelParams, engine::QuantizationType,
+    engine::TimestampGranularity,
+    transcription_engine::TranscriptionEngine, transcription_engine::TranscriptionResult,
 };
-use crate::history;
+use crate::history::{self, HistoryEntry, HistorySource, TranscriptionMetadata};
+use crate::metrics;
+use crate::webhooks::{self, WebhookPayload};
 use crate::model::Model;
 use crate::overlay;
+use crate::profiles::{self, OutputMethod};
+use crate::settings::OverlayMode;
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use hound::{WavSpec, WavWriter};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::BufWriter;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 use tauri::Emitter;
 use tauri::Manager;
@@ -30,49 +39,119 @@ static CURRENT_FILE_NAME: Lazy<parking_lot::Mutex<Option<String>>> =
     Lazy::new(|| parking_lot::Mutex::new(None));
 static ENGINE: Lazy<parking_lot::Mutex<Option<ParakeetEngine>>> =
     Lazy::new(|| parking_lot::Mutex::new(None));
+static RECORDING_STARTED_AT: Lazy<parking_lot::Mutex<Option<Instant>>> =
+    Lazy::new(|| parking_lot::Mutex::new(None));
+// Last smoothed microphone level, stored as f32 bits
+static CURRENT_LEVEL: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Serialize, Clone, Debug, utoipa::ToSchema)]
+pub struct RecordingStatus {
+    pub recording: bool,
+    pub elapsed_ms: u64,
+    pub level: f32,
+}
 
-pub fn record_audio(app: &tauri::AppHandle) {
-    println!("Starting audio recording...");
+#[derive(Serialize, Clone, Debug, utoipa::ToSchema)]
+pub struct EngineInfo {
+    pub loaded: bool,
+    pub quantization: String,
+}
 
-    if RECORDER.lock().is_some() {
-        println!("Already recording");
-        return;
+fn engine_params() -> ParakeetModelParams {
+    ParakeetModelParams::int8()
+}
+
+fn quantization_name() -> &'static str {
+    match engine_params().quantization {
+        QuantizationType::FP32 => "fp32",
+        QuantizationType::Int8 => "int8",
+    }
+}
+
+pub fn engine_info() -> EngineInfo {
+    EngineInfo {
+        // Read from the gauge, the engine lock is held for the whole inference
+        loaded: metrics::ENGINE_LOADED.get() == 1,
+        quantization: quantization_name().to_string(),
     }
+}
 
-    let recordings_dir = match ensure_recordings_dir(app) {
-        Ok(dir) => dir,
-        Err(e) => {
-            eprintln!("Failed to initialize recordings directory: {}", e);
-            return;
+/// Text of an engine run along with what is needed to judge its speed.
+pub struct Transcription {
+    pub text: String,
+    /// Length of the audio, in seconds
+    pub audio_secs: f64,
+    /// Time spent in the engine
+    pub inference: Duration,
+}
+
+impl Transcription {
+    /// History metadata for this run, `raw_text` being the text before dictionary correction.
+    pub fn metadata(&self, app: &AppHandle, source: HistorySource) -> TranscriptionMetadata {
+        let inference_secs = self.inference.as_secs_f64();
+        TranscriptionMetadata {
+            raw_text: Some(self.text.clone()),
+            duration: Some(self.audio_secs),
+            model: Some(app.state::<Arc<Model>>().name().to_string()),
+            quantization: Some(quantization_name().to_string()),
+            inference_ms: Some(self.inference.as_millis() as u64),
+            real_time_factor: (self.audio_secs > 0.0).then(|| inference_secs / self.audio_secs),
+            source: Some(source),
         }
-    };
+    }
+}
+
+pub fn is_recording() -> bool {
+    STREAM.lock().is_some()
+}
+
+pub fn recording_status() -> RecordingStatus {
+    let elapsed_ms = RECORDING_STARTED_AT
+        .lock()
+        .map(|started_at| started_at.elapsed().as_millis() as u64)
+        .unwrap_or(0);
+
+    RecordingStatus {
+        recording: is_recording(),
+        elapsed_ms,
+        level: f32::from_bits(CURRENT_LEVEL.load(Ordering::Relaxed)),
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RecordError {
+    #[error("Already recording")]
+    AlreadyRecording,
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// Start recording from the default microphone. Checking for a recording in progress and
+/// starting one happen under the recorder lock, so two callers can't both start.
+pub fn record_audio(app: &tauri::AppHandle) -> Result<(), RecordError> {
+    println!("Starting audio recording...");
+
+    let mut recorder = RECORDER.lock();
+    if recorder.is_some() {
+        return Err(RecordError::AlreadyRecording);
+    }
+
+    let recordings_dir = ensure_recordings_dir(app).map_err(|e| {
+        RecordError::Failed(format!("Failed to initialize recordings directory: {}", e))
+    })?;
     let file_name = generate_unique_wav_name();
     let file_path = recordings_dir.join(&file_name);
-    *CURRENT_FILE_NAME.lock() = Some(file_name.clone());
 
     let host = cpal::default_host();
-    let device = match host.default_input_device() {
-        Some(d) => d,
-        None => {
-            eprintln!("No input device available");
-            return;
-        }
-    };
-    let config = match device.default_input_config() {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("No input config available: {}", e);
-            return;
-        }
-    };
-
-    let file = match File::create(&file_path) {
-        Ok(f) => f,
-        Err(e) => {
-            eprintln!("Failed to create WAV file: {}", e);
-            return;
-        }
-    };
+    let device = host
+        .default_input_device()
+        .ok_or_else(|| RecordError::Failed("No input device available".to_string()))?;
+    let config = device
+        .default_input_config()
+        .map_err(|e| RecordError::Failed(format!("No input config available: {}", e)))?;
+
+    let file = File::create(&file_path)
+        .map_err(|e| RecordError::Failed(format!("Failed to create WAV file: {}", e)))?;
     let writer = BufWriter::new(file);
     let spec = WavSpec {
         channels: 1,
@@ -80,47 +159,42 @@ pub fn record_audio(app: &tauri::AppHandle) {
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
     };
-    let wav_writer = match WavWriter::new(writer, spec) {
-        Ok(w) => w,
-        Err(e) => {
-            eprintln!("Failed to create WAV writer: {}", e);
-            return;
-        }
-    };
+    let wav_writer = WavWriter::new(writer, spec)
+        .map_err(|e| RecordError::Failed(format!("Failed to create WAV writer: {}", e)))?;
 
     let writer_arc = Arc::new(Mutex::new(Some(wav_writer)));
 
-    *RECORDER.lock() = Some(writer_arc.clone());
-
     let stream = match config.sample_format() {
-        cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, writer_arc, app.clone()),
-        cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, writer_arc, app.clone()),
-        cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config, writer_arc, app.clone()),
-        _ => {
-            eprintln!("Unsupported sample format");
-            return;
+        cpal::SampleFormat::F32 => {
+            build_stream::<f32>(&device, &config, writer_arc.clone(), app.clone())
+        }
+        cpal::SampleFormat::I16 => {
+            build_stream::<i16>(&device, &config, writer_arc.clone(), app.clone())
         }
+        cpal::SampleFormat::I32 => {
+            build_stream::<i32>(&device, &config, writer_arc.clone(), app.clone())
+        }
+        _ => return Err(RecordError::Failed("Unsupported sample format".to_string())),
     };
 
-    match stream.play() {
-        Ok(_) => {}
-        Err(e) => {
-            eprintln!("Failed to start stream: {}", e);
-            return;
-        }
-    }
+    stream
+        .play()
+        .map_err(|e| RecordError::Failed(format!("Failed to start stream: {}", e)))?;
+    *recorder = Some(writer_arc);
+    *CURRENT_FILE_NAME.lock() = Some(file_name);
     *STREAM.lock() = Some(stream);
+    *RECORDING_STARTED_AT.lock() = Some(Instant::now());
+    drop(recorder);
 
     println!("Recording started");
     let s = crate::settings::load_settings(app);
-    if s.overlay_mode.as_str() == "recording" {
+    if s.overlay_mode == OverlayMode::Recording {
         overlay::show_recording_overlay(app);
     }
+    Ok(())
 }
 
-pub fn stop_recording(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
-    println!("Stopping audio recording...");
-
+fn finalize_recorder() {
     if let Some(stream) = STREAM.lock().take() {
         drop(stream);
     }
@@ -132,8 +206,19 @@ pub fn stop_recording(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
             }
         }
     }
+    *RECORDING_STARTED_AT.lock() = None;
+    CURRENT_LEVEL.store(0.0f32.to_bits(), Ordering::Relaxed);
+}
+
+/// Stop the recording, transcribe it and paste the result. Returns the final text, if any.
+pub fn stop_recording(app: &tauri::AppHandle, source: HistorySource) -> Option<String> {
+    println!("Stopping audio recording...");
+
+    let duration = recording_status().elapsed_ms as f64 / 1000.0;
+    finalize_recorder();
 
     if let Some(file_name) = CURRENT_FILE_NAME.lock().take() {
+        let mut transcription = None;
         let path = ensure_recordings_dir(app)
             .map(|dir| dir.join(&file_name))
             .ok();
@@ -141,24 +226,54 @@ pub fn stop_recording(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
             println!("Recording stopped and saved as {}", p.display());
 
             match preload_engine(app) {
-                Ok(_) => match transcribe_audio(p.as_path()) {
-                    Ok(raw_text) => {
-                        println!("Raw transcription: {}", raw_text);
+                Ok(_) => match transcribe_audio_file(p.as_path()) {
+                    Ok(raw) => {
+                        println!("Raw transcription: {}", raw.text);
                         match get_cc_rules_path(app) {
                             Ok(cc_rules_path) => {
+                                let metadata = raw.metadata(app, source);
                                 let dictionary = app.state::<Dictionary>().get();
                                 let text = fix_transcription_with_dictionary(
-                                    raw_text,
+                                    raw.text,
                                     dictionary,
                                     cc_rules_path,
                                 );
                                 println!("Transcription fixed with dictionary: {}", text);
-                                if let Err(e) = history::add_transcription(app, text.clone()) {
-                                    eprintln!("Failed to save to history: {}", e);
-                                }
-                                if let Err(e) = write_transcription(app, &text) {
+                                let text = profiles::post_process(app, text);
+                                let _ = app.emit("transcription-complete", text.clone());
+                                let entry = match history::add_transcription(
+                                    app,
+                                    text.clone(),
+                                    metadata,
+                                ) {
+                                    Ok(entry) => {
+                                        link_recording(app, entry.id, &file_name);
+                                        Some(entry)
+                                    }
+                                    Err(e) => {
+                                        eprintln!("Failed to save to history: {}", e);
+                                        None
+                                    }
+                                };
+                                webhooks::dispatch(
+                                    app,
+                                    WebhookPayload {
+                                        text: text.clone(),
+                                        timestamp: entry
+                                            .as_ref()
+                                            .map(|e| e.timestamp)
+                                            .unwrap_or_else(webhooks::now_secs),
+                                        duration,
+                                        history_id: entry.map(|e| e.id),
+                                    },
+                                );
+                                let output_method = crate::settings::load_settings(app)
+                                    .active_profile()
+                                    .output_method;
+                                if let Err(e) = write_transcription(app, &text, output_method) {
                                     eprintln!("Failed to use clipboard: {}", e);
                                 }
+                                transcription = Some(text);
                             }
                             Err(e) => {
                                 eprintln!("Failed to get CC rules path: {}", e);
@@ -180,28 +295,141 @@ pub fn stop_recording(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
         // Emit a final zero level to let frontend reset visualizer
         let _ = app.emit("mic-level", 0.0f32);
         let s = crate::settings::load_settings(app);
-        if s.overlay_mode.as_str() == "recording" {
+        if s.overlay_mode == OverlayMode::Recording {
             overlay::hide_recording_overlay(app);
         }
-        return path;
+        return transcription;
     } else {
         println!("Recording stopped");
     }
     None
 }
 
+/// Apply the dictionary and the active profile's post-processing to a transcription.
+pub fn correct_transcription(app: &tauri::AppHandle, text: String) -> String {
+    let text = match get_cc_rules_path(app) {
+        Ok(cc_rules_path) => {
+            let dictionary = app.state::<Dictionary>().get();
+            fix_transcription_with_dictionary(text, dictionary, cc_rules_path)
+        }
+        Err(_) => text,
+    };
+    profiles::post_process(app, text)
+}
+
+/// Correct the transcription of an uploaded file and save it to the history as a file import.
+pub fn finish_upload(app: &tauri::AppHandle, raw: Transcription) -> String {
+    let metadata = raw.metadata(app, HistorySource::FileImport);
+    let text = correct_transcription(app, raw.text);
+    if let Err(e) = history::add_transcription(app, text.clone(), metadata) {
+        eprintln!("Failed to save to history: {}", e);
+    }
+    text
+}
+
+/// How `retranscribe` treats the new text.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct RetranscribeOptions {
+    /// Correct the new text with the current dictionary
+    pub use_dictionary: bool,
+    /// Save the new text as a separate entry instead of replacing the original one
+    pub fork: bool,
+}
+
+impl Default for RetranscribeOptions {
+    fn default() -> Self {
+        Self {
+            use_dictionary: true,
+            fork: false,
+        }
+    }
+}
+
+/// Transcribe the kept recording of a history entry again, with the current model and dictionary.
+pub fn retranscribe(
+    app: &tauri::AppHandle,
+    id: u64,
+    options: &RetranscribeOptions,
+) -> Result<HistoryEntry> {
+    let entry = history::get_transcription(app, id)?
+        .ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))?;
+    let file_name = entry
+        .audio_file
+        .ok_or_else(|| anyhow::anyhow!("No recording was kept for this entry"))?;
+    let path = ensure_recordings_dir(app)?.join(&file_name);
+    if !path.exists() {
+        return Err(anyhow::anyhow!("Recording {} no longer exists", file_name));
+    }
+
+    preload_engine(app)?;
+    let raw = transcribe_audio_file(&path)?;
+    let mut metadata = raw.metadata(app, HistorySource::Shortcut);
+    // Still the same dictation, keep where it came from
+    metadata.source = entry.metadata.source;
+    let text = if options.use_dictionary {
+        let cc_rules_path = get_cc_rules_path(app)?;
+        let dictionary = app.state::<Dictionary>().get();
+        let text = fix_transcription_with_dictionary(raw.text, dictionary, cc_rules_path);
+        profiles::post_process(app, text)
+    } else {
+        raw.text
+    };
+
+    if options.fork {
+        let forked = history::add_transcription(app, text, metadata)?;
+        history::set_audio_file(app, forked.id, Some(&file_name))?;
+        Ok(HistoryEntry {
+            audio_file: Some(file_name),
+            ..forked
+        })
+    } else {
+        history::update_transcription(app, id, text, metadata)?
+            .ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))
+    }
+}
+
+/// Stop the recording and discard the audio without transcribing it.
+pub fn cancel_recording(app: &tauri::AppHandle) {
+    println!("Cancelling audio recording...");
+
+    finalize_recorder();
+
+    if let Some(file_name) = CURRENT_FILE_NAME.lock().take() {
+        if let Ok(dir) = ensure_recordings_dir(app) {
+            let path = dir.join(&file_name);
+            if let Err(e) = std::fs::remove_file(&path) {
+                eprintln!("Failed to delete {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    let _ = app.emit("mic-level", 0.0f32);
+    let s = crate::settings::load_settings(app);
+    if s.overlay_mode == OverlayMode::Recording {
+        overlay::hide_recording_overlay(app);
+    }
+    println!("Recording cancelled");
+}
+
 pub fn write_transcription(
     app: &tauri::AppHandle,
     transcription: &str,
+    method: OutputMethod,
 ) -> Result<(), anyhow::Error> {
-    if let Err(e) = clipboard::paste(transcription, app) {
+    let result = match method {
+        OutputMethod::Paste => clipboard::paste(transcription, app),
+        OutputMethod::Clipboard => clipboard::copy(transcription, app),
+        OutputMethod::None => Ok(()),
+    };
+    if let Err(e) = result {
         eprintln!("Failed to paste text: {}", e);
     }
 
     if let Err(e) = cleanup_recordings(app) {
         eprintln!("Failed to cleanup recordings: {}", e);
     } else {
-        println!("All recordings cleaned up");
+        println!("Recordings cleaned up");
     }
 
     println!("Transcription written to clipboard {}", transcription);
@@ -209,7 +437,9 @@ pub fn write_transcription(
 }
 
 pub fn read_wav_samples(wav_path: &std::path::Path) -> Result<Vec<f32>> {
-    let mut reader = hound::WavReader::open(wav_path)?;
+    // Kept recordings are encrypted when encryption is enabled
+    let data = crate::encryption::read_file(wav_path)?;
+    let mut reader = hound::WavReader::new(std::io::Cursor::new(data))?;
     let spec = reader.spec();
 
     if spec.bits_per_sample != 16 {
@@ -265,29 +495,58 @@ pub fn preload_engine(app: &tauri::AppHandle) -> Result<()> {
 
         let mut new_engine = ParakeetEngine::new();
         new_engine
-            .load_model_with_params(&model_path, ParakeetModelParams::int8())
+            .load_model_with_params(&model_path, engine_params())
             .map_err(|e| anyhow::anyhow!("Failed to load model: {}", e))?;
 
         *engine = Some(new_engine);
+        metrics::ENGINE_LOADED.set(1);
         println!("Model loaded and cached in memory");
     }
 
     Ok(())
 }
 
-pub fn transcribe_audio(audio_path: &std::path::Path) -> Result<String> {
+pub fn transcribe_audio_file(audio_path: &std::path::Path) -> Result<Transcription> {
     let samples = read_wav_samples(audio_path)?;
+    let audio_secs = samples.len() as f64 / 16000.0;
 
     let mut engine = ENGINE.lock();
     let engine = engine
         .as_mut()
         .ok_or_else(|| anyhow::anyhow!("Engine not loaded"))?;
 
+    let started_at = Instant::now();
     let result = engine
         .transcribe_samples(samples, None)
         .map_err(|e| anyhow::anyhow!("Transcription failed: {}", e))?;
+    let inference = started_at.elapsed();
+    metrics::observe_transcription(audio_secs, inference);
+
+    Ok(Transcription {
+        text: result.text,
+        audio_secs,
+        inference,
+    })
+}
+
+pub fn transcribe_samples_with_timestamps(
+    samples: Vec<f32>,
+    granularities: &[TimestampGranularity],
+) -> Result<Vec<TranscriptionResult>> {
+    let audio_secs = samples.len() as f64 / 16000.0;
+
+    let mut engine = ENGINE.lock();
+    let engine = engine
+        .as_mut()
+        .ok_or_else(|| anyhow::anyhow!("Engine not loaded"))?;
+
+    let started_at = Instant::now();
+    let results = engine
+        .transcribe_samples_with_granularities(samples, granularities)
+        .map_err(|e| anyhow::anyhow!("Transcription failed: {}", e))?;
+    metrics::observe_transcription(audio_secs, started_at.elapsed());
 
-    Ok(result.text)
+    Ok(results)
 }
 
 fn ensure_recordings_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
@@ -304,6 +563,27 @@ fn ensure_recordings_dir(app: &tauri::AppHandle) -> Result<PathBuf> {
     Ok(recordings)
 }
 
+/// Link the recording to its history entry when recordings are kept.
+fn link_recording(app: &tauri::AppHandle, id: u64, file_name: &str) {
+    if !crate::settings::load_settings(app).keep_recordings {
+        return;
+    }
+    if let Err(e) = history::set_audio_file(app, id, Some(file_name)) {
+        eprintln!("Failed to link recording to history: {}", e);
+    }
+    let path = match ensure_recordings_dir(app) {
+        Ok(dir) => dir.join(file_name),
+        Err(e) => {
+            eprintln!("Failed to resolve recordings directory: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = crate::encryption::seal_file(&path) {
+        eprintln!("Failed to encrypt {}: {:#}", path.display(), e);
+    }
+}
+
+/// Delete recordings, or only those outside the retention policy when recordings are kept.
 fn cleanup_recordings(app: &tauri::AppHandle) -> Result<()> {
     let recordings_dir = ensure_recordings_dir(app)?;
 
@@ -314,12 +594,60 @@ fn cleanup_recordings(app: &tauri::AppHandle) -> Result<()> {
     let entries =
         std::fs::read_dir(&recordings_dir).context("Failed to read recordings directory")?;
 
-    for entry in entries {
-        if let Ok(entry) = entry {
-            if entry.path().is_file() {
-                if let Err(e) = std::fs::remove_file(entry.path()) {
-                    eprintln!("Failed to delete {}: {}", entry.path().display(), e);
+    // A recording may be in progress, never touch its file
+    let current = CURRENT_FILE_NAME.lock().clone();
+    let mut recordings: Vec<(PathBuf, std::fs::Metadata)> = entries
+        .flatten()
+        .filter(|entry| Some(entry.file_name().to_string_lossy().as_ref()) != current.as_deref())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| (entry.path(), metadata))
+        })
+        .collect();
+
+    let s = crate::settings::load_settings(app);
+    let expired = if s.keep_recordings {
+        // Most recent first, the oldest go first once a limit is reached
+        recordings.sort_by_key(|(_, metadata)| std::cmp::Reverse(metadata.modified().ok()));
+        let linked = history::audio_files(app)?;
+        let max_age = Duration::from_secs(s.recordings_retention_days as u64 * 24 * 60 * 60);
+        let max_size = s.recordings_max_size_mb * 1024 * 1024;
+        let mut kept_count = 0;
+        let mut kept_size = 0;
+
+        recordings
+            .into_iter()
+            .filter(|(path, metadata)| {
+                let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+                let age = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .unwrap_or_default();
+                let keep = name.is_some_and(|name| linked.contains(&name))
+                    && (s.recordings_max_count == 0 || kept_count < s.recordings_max_count)
+                    && (s.recordings_retention_days == 0 || age <= max_age)
+                    && (s.recordings_max_size_mb == 0 || kept_size + metadata.len() <= max_size);
+                if keep {
+                    kept_count += 1;
+                    kept_size += metadata.len();
                 }
+                !keep
+            })
+            .map(|(path, _)| path)
+            .collect()
+    } else {
+        recordings.into_iter().map(|(path, _)| path).collect::<Vec<_>>()
+    };
+
+    for path in expired {
+        if let Err(e) = std::fs::remove_file(&path) {
+            eprintln!("Failed to delete {}: {}", path.display(), e);
+            continue;
+        }
+        if let Some(name) = path.file_name() {
+            if let Err(e) = history::forget_audio_file(app, &name.to_string_lossy()) {
+                eprintln!("Failed to unlink {} from history: {}", path.display(), e);
             }
         }
     }
@@ -331,7 +659,7 @@ fn generate_unique_wav_name() -> String {
     let ts = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
-        .as_secs();
+        .as_millis();
     format!("murmure-{}.wav", ts)
 }
 
@@ -392,6 +720,7 @@ where
                         }
                         // EMA smoothing
                         ema_level = alpha * level + (1.0 - alpha) * ema_level;
+                        CURRENT_LEVEL.store(ema_level.to_bits(), Ordering::Relaxed);
                         let _ = app.emit("mic-level", ema_level);
                         // also forward to overlay window if present
                         if let Some(overlay_window) = app.get_webview_window("recording_overlay") {
@@ -400,6 +729,7 @@ where
                         acc_sum_squares = 0.0;
                         acc_count = 0;
                     } else {
+                        CURRENT_LEVEL.store(0.0f32.to_bits(), Ordering::Relaxed);
                         let _ = app.emit("mic-level", 0.0f32);
                         if let Some(overlay_window) = app.get_webview_window("recording_overlay") {
                             let _ = overlay_window.emit("mic-level", 0.0f32);
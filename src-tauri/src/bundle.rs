@@ -0,0 +1,207 @@
+use crate::settings::{self, FieldError, SettingsChange, SettingsError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Layout version of a bundle, bump it when `Bundle` changes shape.
+const BUNDLE_VERSION: u32 = 1;
+
+/// Path of a bundle applied on every start, to roll out team-wide settings.
+pub const BUNDLE_ENV: &str = "MURMURE_CONFIG_BUNDLE";
+
+/// Settings that belong to this machine, they are neither exported nor imported. Webhooks
+/// carry their URLs and authorization headers, the bind settings decide who can reach the API.
+const LOCAL_FIELDS: &[&str] = &[
+    "api_key",
+    "webhooks",
+    "api_bind_address",
+    "api_allow_external_bind",
+    "api_socket_path",
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleFormat {
+    Json,
+    Toml,
+}
+
+impl BundleFormat {
+    /// TOML for `.toml` files, JSON for anything else.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => BundleFormat::Toml,
+            _ => BundleFormat::Json,
+        }
+    }
+}
+
+/// A portable copy of the configuration. `settings` holds `settings.json` fields, the profiles
+/// carry the dictionaries and post-processing rules. Fields left out keep their current value.
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    #[serde(default)]
+    bundle_version: u32,
+    settings: Map<String, Value>,
+}
+
+/// What importing a bundle would change.
+#[derive(Serialize, Clone, Debug)]
+pub struct BundlePreview {
+    pub changes: Vec<SettingsChange>,
+    /// Values the import would refuse, nothing is applied unless this is empty
+    pub errors: Vec<FieldError>,
+}
+
+pub fn export_bundle(app: &AppHandle, format: BundleFormat) -> Result<String, String> {
+    let Ok(Value::Object(mut settings)) = serde_json::to_value(settings::load_settings(app)) else {
+        return Err("Failed to read the settings".to_string());
+    };
+    for field in LOCAL_FIELDS {
+        settings.remove(*field);
+    }
+    let bundle = Bundle {
+        bundle_version: BUNDLE_VERSION,
+        settings,
+    };
+
+    match format {
+        BundleFormat::Json => serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string()),
+        BundleFormat::Toml => {
+            // TOML has no null
+            let mut value = serde_json::to_value(&bundle).map_err(|e| e.to_string())?;
+            strip_nulls(&mut value);
+            toml::to_string_pretty(&value).map_err(|e| e.to_string())
+        }
+    }
+}
+
+pub fn write_bundle(app: &AppHandle, path: &Path) -> Result<(), String> {
+    let content = export_bundle(app, BundleFormat::from_path(path))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// The settings fields of a bundle, without the ones that stay on this machine.
+pub fn parse_bundle(content: &str, format: BundleFormat) -> Result<Map<String, Value>, String> {
+    let value = match format {
+        BundleFormat::Json => {
+            serde_json::from_str(content).map_err(|e| format!("Invalid bundle: {}", e))?
+        }
+        BundleFormat::Toml => {
+            let value: toml::Value =
+                toml::from_str(content).map_err(|e| format!("Invalid bundle: {}", e))?;
+            serde_json::to_value(value).map_err(|e| format!("Invalid bundle: {}", e))?
+        }
+    };
+    let bundle: Bundle =
+        serde_json::from_value(value).map_err(|e| format!("Invalid bundle: {}", e))?;
+    if bundle.bundle_version > BUNDLE_VERSION {
+        return Err(format!(
+            "The bundle was made by a newer version of Murmure (bundle version {})",
+            bundle.bundle_version
+        ));
+    }
+
+    let mut settings = bundle.settings;
+    for field in LOCAL_FIELDS {
+        settings.remove(*field);
+    }
+    Ok(settings)
+}
+
+pub fn read_bundle(path: &Path) -> Result<Map<String, Value>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_bundle(&content, BundleFormat::from_path(path))
+}
+
+/// What importing `fields` would change, without applying anything.
+pub fn preview_bundle(
+    app: &AppHandle,
+    fields: Map<String, Value>,
+) -> Result<BundlePreview, String> {
+    let current = settings::load_settings(app);
+    let merged = match settings::merge_settings(&current, fields) {
+        Ok(merged) => merged,
+        Err(e) if e.fields.is_empty() => return Err(e.message),
+        Err(e) => {
+            return Ok(BundlePreview {
+                changes: Vec::new(),
+                errors: e.fields,
+            })
+        }
+    };
+
+    let changes = settings::diff_settings(&current, &merged);
+    let errors = merged
+        .validate()
+        .into_iter()
+        .filter(|e| changes.iter().any(|change| change.field == e.field))
+        .collect();
+    Ok(BundlePreview { changes, errors })
+}
+
+/// Apply `fields` over the current settings, returns what changed.
+pub fn import_bundle(
+    app: &AppHandle,
+    fields: Map<String, Value>,
+) -> Result<Vec<SettingsChange>, SettingsError> {
+    let changes = save_bundle(app, fields)?;
+    settings::apply_runtime_settings(app, &changes);
+    Ok(changes)
+}
+
+/// Write the merged settings, all or nothing. The state derived from them is left alone.
+fn save_bundle(
+    app: &AppHandle,
+    fields: Map<String, Value>,
+) -> Result<Vec<SettingsChange>, SettingsError> {
+    let before = settings::load_settings(app);
+    // Nothing is written when the bundle changes nothing
+    let merged = settings::merge_settings(&before, fields.clone())?;
+    if settings::diff_settings(&before, &merged).is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Merged again, the settings may have changed in the meantime
+    let after = settings::try_update_settings(app, |s| {
+        *s = settings::merge_settings(s, fields)?;
+        Ok(())
+    })?;
+    Ok(settings::diff_settings(&before, &after))
+}
+
+/// Apply the bundle named by `MURMURE_CONFIG_BUNDLE`. Runs before the shortcuts, overlay and
+/// API are set up, so they start from the bundled settings.
+pub fn apply_startup_bundle(app: &AppHandle) {
+    let Some(path) = std::env::var_os(BUNDLE_ENV).filter(|path| !path.is_empty()) else {
+        return;
+    };
+    let path = PathBuf::from(path);
+
+    match read_bundle(&path)
+        .map_err(SettingsError::from)
+        .and_then(|fields| save_bundle(app, fields))
+    {
+        Ok(changes) if changes.is_empty() => {}
+        Ok(changes) => println!(
+            "Applied config bundle {}, {} setting(s) changed",
+            path.display(),
+            changes.len()
+        ),
+        Err(e) => eprintln!("Failed to apply config bundle {}: {}", path.display(), e),
+    }
+}
+
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, value| !value.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
@@ -27,6 +27,14 @@ pub fn paste(text: &str, app_handle: &tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Put `text` in the clipboard without pasting it.
+pub fn copy(text: &str, app_handle: &tauri::AppHandle) -> Result<(), String> {
+    app_handle
+        .clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))
+}
+
 fn send_paste() -> Result<(), String> {
     #[cfg(target_os = "macos")]
     let (modifier_key, v_key_code) = (Key::Meta, Key::Other(9));
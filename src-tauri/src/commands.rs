@@ -1,15 +1,27 @@
-use crate::dictionary::Dictionary;
-use crate::history::{self, HistoryEntry};
+use crate::audio::{self, RetranscribeOptions};
+use crate::bundle::{self, BundlePreview};
+use crate::dictionary::{Dictionary, DictionaryEntry};
+use crate::encryption::{self, EncryptionStatus};
+use crate::history::{
+    self, ExportFormat, HistoryEntry, HistoryFilter, HistoryImport, ImportSummary,
+};
 use crate::model::Model;
-use crate::settings;
+use crate::profiles::{self, OutputMethod, PostProcessingRule, ProfileList};
+use crate::settings::{
+    self, FieldError, OverlayMode, OverlayPosition, SettingsChange, SettingsError,
+};
+use crate::storage::{self, Recovery};
 use crate::shortcuts::{
-    keys_to_string, parse_binding_keys, LastTranscriptShortcutKeys, RecordShortcutKeys,
-    TranscriptionSuspended,
+    keys_to_string, parse_binding_keys, LastTranscriptShortcutKeys, ProfileShortcutKeys,
+    RecordShortcutKeys, TranscriptionSuspended,
 };
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager, State};
-use crate::http_api::HttpApiState;
-use crate::http_api::spawn_http_api_thread;
+use tauri::{AppHandle, Emitter, Manager, State};
+use crate::http_api::ApiBind;
+use crate::webhooks::{WebhookConfig, WebhookDelivery, WebhookLog};
+use crate::http_api::{ApiStatus, HttpApiState};
 
 #[tauri::command]
 pub fn is_model_available(model: State<Arc<Model>>) -> bool {
@@ -28,23 +40,103 @@ pub fn get_recent_transcriptions(app: AppHandle) -> Result<Vec<HistoryEntry>, St
     history::get_recent_transcriptions(&app).map_err(|e| format!("{:#}", e))
 }
 
+#[derive(Serialize)]
+pub struct HistorySearchResult {
+    pub entries: Vec<HistoryEntry>,
+    pub total: usize,
+}
+
+#[tauri::command]
+pub fn search_history(
+    app: AppHandle,
+    filter: HistoryFilter,
+    offset: usize,
+    limit: usize,
+) -> Result<HistorySearchResult, String> {
+    let (entries, total) = history::search_transcriptions(&app, &filter, offset, limit)
+        .map_err(|e| format!("{:#}", e))?;
+    Ok(HistorySearchResult { entries, total })
+}
+
+#[tauri::command]
+pub fn export_history(
+    app: AppHandle,
+    filter: HistoryFilter,
+    format: ExportFormat,
+) -> Result<String, String> {
+    history::export_transcriptions(&app, &filter, format).map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+pub fn import_history(app: AppHandle, content: String) -> Result<ImportSummary, String> {
+    let data: HistoryImport =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid history file: {}", e))?;
+    history::import_transcriptions(&app, data).map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+pub async fn retranscribe_history_entry(
+    app: AppHandle,
+    id: u64,
+    options: RetranscribeOptions,
+) -> Result<HistoryEntry, String> {
+    // Inference is synchronous, keep it off the main thread
+    tauri::async_runtime::spawn_blocking(move || audio::retranscribe(&app, id, &options))
+        .await
+        .map_err(|e| format!("Re-transcription task failed: {}", e))?
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RecordingsRetention {
+    pub keep_recordings: bool,
+    pub max_count: usize,
+    pub retention_days: u32,
+    pub max_size_mb: u64,
+}
+
+#[tauri::command]
+pub fn get_recordings_retention(app: AppHandle) -> Result<RecordingsRetention, String> {
+    let s = settings::load_settings(&app);
+    Ok(RecordingsRetention {
+        keep_recordings: s.keep_recordings,
+        max_count: s.recordings_max_count,
+        retention_days: s.recordings_retention_days,
+        max_size_mb: s.recordings_max_size_mb,
+    })
+}
+
+#[tauri::command]
+pub fn set_recordings_retention(
+    app: AppHandle,
+    retention: RecordingsRetention,
+) -> Result<(), SettingsError> {
+    settings::update_settings(&app, |s| {
+        s.keep_recordings = retention.keep_recordings;
+        s.recordings_max_count = retention.max_count;
+        s.recordings_retention_days = retention.retention_days;
+        s.recordings_max_size_mb = retention.max_size_mb;
+    })?;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn get_record_shortcut(app: AppHandle) -> Result<String, String> {
     let s = settings::load_settings(&app);
-    Ok(s.record_shortcut)
+    Ok(s.active_profile().record_shortcut.clone())
 }
 
 #[tauri::command]
-pub fn set_record_shortcut(app: AppHandle, binding: String) -> Result<String, String> {
+pub fn set_record_shortcut(app: AppHandle, binding: String) -> Result<String, SettingsError> {
     let keys = parse_binding_keys(&binding);
     if keys.is_empty() {
-        return Err("Invalid shortcut".to_string());
+        return Err(FieldError::new("profiles", "Invalid record shortcut").into());
     }
     let normalized = keys_to_string(&keys);
 
-    let mut s = settings::load_settings(&app);
-    s.record_shortcut = normalized.clone();
-    settings::save_settings(&app, &s)?;
+    settings::update_settings(&app, |s| {
+        s.active_profile_mut().record_shortcut = normalized.clone()
+    })?;
 
     app.state::<RecordShortcutKeys>().set(keys);
 
@@ -52,10 +144,11 @@ pub fn set_record_shortcut(app: AppHandle, binding: String) -> Result<String, St
 }
 
 #[tauri::command]
-pub fn set_dictionary(app: AppHandle, dictionary: Vec<String>) -> Result<(), String> {
-    let mut s = settings::load_settings(&app);
-    s.dictionary = dictionary.clone();
-    settings::save_settings(&app, &s)?;
+pub fn set_dictionary(
+    app: AppHandle,
+    dictionary: Vec<DictionaryEntry>,
+) -> Result<(), SettingsError> {
+    settings::update_settings(&app, |s| s.active_profile_mut().dictionary = dictionary.clone())?;
 
     app.state::<Dictionary>().set(dictionary.clone());
 
@@ -63,28 +156,31 @@ pub fn set_dictionary(app: AppHandle, dictionary: Vec<String>) -> Result<(), Str
 }
 
 #[tauri::command]
-pub fn get_dictionary(app: AppHandle) -> Result<Vec<String>, String> {
+pub fn get_dictionary(app: AppHandle) -> Result<Vec<DictionaryEntry>, String> {
     let s = settings::load_settings(&app);
-    Ok(s.dictionary)
+    Ok(s.active_profile().dictionary.clone())
 }
 
 #[tauri::command]
 pub fn get_last_transcript_shortcut(app: AppHandle) -> Result<String, String> {
     let s = settings::load_settings(&app);
-    Ok(s.last_transcript_shortcut)
+    Ok(s.active_profile().last_transcript_shortcut.clone())
 }
 
 #[tauri::command]
-pub fn set_last_transcript_shortcut(app: AppHandle, binding: String) -> Result<String, String> {
+pub fn set_last_transcript_shortcut(
+    app: AppHandle,
+    binding: String,
+) -> Result<String, SettingsError> {
     let keys = parse_binding_keys(&binding);
     if keys.is_empty() {
-        return Err("Invalid shortcut".to_string());
+        return Err(FieldError::new("profiles", "Invalid last transcript shortcut").into());
     }
     let normalized = keys_to_string(&keys);
 
-    let mut s = settings::load_settings(&app);
-    s.last_transcript_shortcut = normalized.clone();
-    settings::save_settings(&app, &s)?;
+    settings::update_settings(&app, |s| {
+        s.active_profile_mut().last_transcript_shortcut = normalized.clone()
+    })?;
 
     app.state::<LastTranscriptShortcutKeys>().set(keys);
 
@@ -104,47 +200,36 @@ pub fn resume_transcription(app: AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn get_overlay_mode(app: AppHandle) -> Result<String, String> {
+pub fn get_overlay_mode(app: AppHandle) -> Result<OverlayMode, String> {
     let s = settings::load_settings(&app);
     Ok(s.overlay_mode)
 }
 
 #[tauri::command]
-pub fn set_overlay_mode(app: AppHandle, mode: String) -> Result<(), String> {
-    let allowed = ["hidden", "recording", "always"];
-    if !allowed.contains(&mode.as_str()) {
-        return Err("Invalid overlay mode".to_string());
-    }
-    let mut s = settings::load_settings(&app);
-    s.overlay_mode = mode;
-    let res = settings::save_settings(&app, &s);
-    match s.overlay_mode.as_str() {
-        "always" => {
+pub fn set_overlay_mode(app: AppHandle, mode: String) -> Result<(), SettingsError> {
+    let mode: OverlayMode = mode.parse()?;
+    let res = settings::update_settings(&app, |s| s.overlay_mode = mode).map(|_| ());
+    match mode {
+        OverlayMode::Always => {
             crate::overlay::show_recording_overlay(&app);
         }
-        "hidden" | "recording" => {
+        OverlayMode::Hidden | OverlayMode::Recording => {
             crate::overlay::hide_recording_overlay(&app);
         }
-        _ => {}
     }
     res
 }
 
 #[tauri::command]
-pub fn get_overlay_position(app: AppHandle) -> Result<String, String> {
+pub fn get_overlay_position(app: AppHandle) -> Result<OverlayPosition, String> {
     let s = settings::load_settings(&app);
     Ok(s.overlay_position)
 }
 
 #[tauri::command]
-pub fn set_overlay_position(app: AppHandle, position: String) -> Result<(), String> {
-    let allowed = ["top", "bottom"];
-    if !allowed.contains(&position.as_str()) {
-        return Err("Invalid overlay position".to_string());
-    }
-    let mut s = settings::load_settings(&app);
-    s.overlay_position = position;
-    let res = settings::save_settings(&app, &s);
+pub fn set_overlay_position(app: AppHandle, position: String) -> Result<(), SettingsError> {
+    let position: OverlayPosition = position.parse()?;
+    let res = settings::update_settings(&app, |s| s.overlay_position = position).map(|_| ());
     crate::overlay::update_overlay_position(&app);
     res
 }
@@ -156,10 +241,10 @@ pub fn get_api_enabled(app: AppHandle) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub fn set_api_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
-    let mut s = settings::load_settings(&app);
-    s.api_enabled = enabled;
-    settings::save_settings(&app, &s)
+pub fn set_api_enabled(app: AppHandle, enabled: bool) -> Result<(), SettingsError> {
+    settings::update_settings(&app, |s| s.api_enabled = enabled)?;
+    app.state::<HttpApiState>().apply();
+    Ok(())
 }
 
 #[tauri::command]
@@ -169,24 +254,113 @@ pub fn get_api_port(app: AppHandle) -> Result<u16, String> {
 }
 
 #[tauri::command]
-pub fn set_api_port(app: AppHandle, port: u16) -> Result<(), String> {
-    if port < 1024 {
-        return Err("Port must be >= 1024".to_string());
-    }
-    let mut s = settings::load_settings(&app);
-    s.api_port = port;
-    settings::save_settings(&app, &s)
+pub fn set_api_port(app: AppHandle, port: u16) -> Result<(), SettingsError> {
+    settings::update_settings(&app, |s| s.api_port = port)?;
+    app.state::<HttpApiState>().apply();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_api_key(app: AppHandle) -> Result<String, String> {
+    settings::ensure_api_key(&app)
+}
+
+#[tauri::command]
+pub fn regenerate_api_key(app: AppHandle) -> Result<String, String> {
+    let s = settings::update_settings(&app, |s| s.api_key = settings::generate_api_key())?;
+    Ok(s.api_key)
+}
+
+#[tauri::command]
+pub fn get_api_cors_origins(app: AppHandle) -> Result<Vec<String>, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.api_cors_origins)
+}
+
+#[tauri::command]
+pub fn set_api_cors_origins(app: AppHandle, origins: Vec<String>) -> Result<(), SettingsError> {
+    let origins = origins
+        .into_iter()
+        .map(|origin| origin.trim().trim_end_matches('/').to_string())
+        .filter(|origin| !origin.is_empty())
+        .collect();
+    settings::update_settings(&app, |s| s.api_cors_origins = origins)?;
+    app.state::<HttpApiState>().apply();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_api_bind_address(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.api_bind_address)
+}
+
+#[tauri::command]
+pub fn set_api_bind_address(app: AppHandle, address: String) -> Result<(), SettingsError> {
+    settings::try_update_settings(&app, |s| {
+        s.api_bind_address = address.trim().to_string();
+        Ok(())
+    })?;
+    app.state::<HttpApiState>().apply();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_api_allow_external_bind(app: AppHandle) -> Result<bool, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.api_allow_external_bind)
+}
+
+#[tauri::command]
+pub fn set_api_allow_external_bind(app: AppHandle, allow: bool) -> Result<(), SettingsError> {
+    settings::try_update_settings(&app, |s| {
+        s.api_allow_external_bind = allow;
+        Ok(())
+    })?;
+    app.state::<HttpApiState>().apply();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_api_socket_path(app: AppHandle) -> Result<String, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.api_socket_path)
+}
+
+#[tauri::command]
+pub fn set_api_socket_path(app: AppHandle, path: String) -> Result<(), SettingsError> {
+    settings::try_update_settings(&app, |s| {
+        s.api_socket_path = path.trim().to_string();
+        Ok(())
+    })?;
+    app.state::<HttpApiState>().apply();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_webhooks(app: AppHandle) -> Result<Vec<WebhookConfig>, String> {
+    let s = settings::load_settings(&app);
+    Ok(s.webhooks)
+}
+
+#[tauri::command]
+pub fn set_webhooks(app: AppHandle, webhooks: Vec<WebhookConfig>) -> Result<(), SettingsError> {
+    settings::update_settings(&app, |s| s.webhooks = webhooks)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_webhook_deliveries(app: AppHandle) -> Result<Vec<WebhookDelivery>, String> {
+    Ok(app.state::<WebhookLog>().get())
 }
 
 #[tauri::command]
 pub fn start_http_api_server(app: AppHandle) -> Result<String, String> {
     let s = settings::load_settings(&app);
-    let port = s.api_port;
-    let app_handle = app.clone();
-    let state = app.state::<HttpApiState>().inner().clone();
-    spawn_http_api_thread(app_handle, port, state);
+    let bind = ApiBind::from_settings(&s)?;
+    app.state::<HttpApiState>().start();
 
-    Ok(format!("HTTP API server starting on port {}", s.api_port))
+    Ok(format!("HTTP API server starting on {}", bind))
 }
 
 #[tauri::command]
@@ -196,3 +370,126 @@ pub fn stop_http_api_server(app: AppHandle) -> Result<(), String> {
     eprintln!("HTTP API server stop signal sent");
     Ok(())
 }
+
+#[tauri::command]
+pub fn get_api_status(app: AppHandle) -> Result<ApiStatus, String> {
+    Ok(app.state::<HttpApiState>().status())
+}
+
+#[tauri::command]
+pub fn get_encryption_status() -> Result<EncryptionStatus, String> {
+    Ok(encryption::status())
+}
+
+#[tauri::command]
+pub fn enable_encryption(app: AppHandle, passphrase: Option<String>) -> Result<(), String> {
+    encryption::enable(&app, passphrase.as_deref()).map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+pub fn disable_encryption(app: AppHandle) -> Result<(), String> {
+    encryption::disable(&app).map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+pub fn rotate_encryption_key(app: AppHandle, passphrase: Option<String>) -> Result<(), String> {
+    encryption::rotate(&app, passphrase.as_deref()).map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+pub fn unlock_encryption(app: AppHandle, passphrase: String) -> Result<(), String> {
+    encryption::unlock(&app, &passphrase).map_err(|e| format!("{:#}", e))?;
+
+    // Everything read at startup only saw the defaults
+    settings::reload_settings(&app)?;
+    let _ = app.emit("encryption-unlocked", ());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn take_storage_recoveries() -> Result<Vec<Recovery>, String> {
+    Ok(storage::take_recoveries())
+}
+
+#[tauri::command]
+pub fn get_profiles(app: AppHandle) -> Result<ProfileList, String> {
+    Ok(ProfileList::from(settings::load_settings(&app)))
+}
+
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, name: String) -> Result<ProfileList, SettingsError> {
+    profiles::switch_profile(&app, &name).map(ProfileList::from)
+}
+
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String) -> Result<ProfileList, SettingsError> {
+    profiles::create_profile(&app, &name).map(ProfileList::from)
+}
+
+#[tauri::command]
+pub fn rename_profile(
+    app: AppHandle,
+    name: String,
+    new_name: String,
+) -> Result<ProfileList, SettingsError> {
+    profiles::rename_profile(&app, &name, &new_name).map(ProfileList::from)
+}
+
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, name: String) -> Result<ProfileList, SettingsError> {
+    profiles::delete_profile(&app, &name).map(ProfileList::from)
+}
+
+#[tauri::command]
+pub fn set_output_method(app: AppHandle, method: OutputMethod) -> Result<(), SettingsError> {
+    settings::update_settings(&app, |s| s.active_profile_mut().output_method = method)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_post_processing_rules(
+    app: AppHandle,
+    rules: Vec<PostProcessingRule>,
+) -> Result<(), SettingsError> {
+    settings::update_settings(&app, |s| s.active_profile_mut().post_processing = rules)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_profile_shortcut(app: AppHandle) -> Result<String, String> {
+    Ok(settings::load_settings(&app).profile_shortcut)
+}
+
+#[tauri::command]
+pub fn set_profile_shortcut(app: AppHandle, binding: String) -> Result<String, SettingsError> {
+    // An empty binding turns the shortcut off
+    let keys = parse_binding_keys(&binding);
+    if keys.is_empty() && !binding.trim().is_empty() {
+        return Err(FieldError::new("profile_shortcut", "Invalid shortcut").into());
+    }
+    let normalized = keys_to_string(&keys);
+
+    settings::update_settings(&app, |s| s.profile_shortcut = normalized.clone())?;
+
+    app.state::<ProfileShortcutKeys>().set(keys);
+
+    Ok(normalized)
+}
+
+#[tauri::command]
+pub fn export_config_bundle(app: AppHandle, path: String) -> Result<(), String> {
+    bundle::write_bundle(&app, Path::new(&path))
+}
+
+#[tauri::command]
+pub fn preview_config_bundle(app: AppHandle, path: String) -> Result<BundlePreview, String> {
+    bundle::preview_bundle(&app, bundle::read_bundle(Path::new(&path))?)
+}
+
+#[tauri::command]
+pub fn import_config_bundle(
+    app: AppHandle,
+    path: String,
+) -> Result<Vec<SettingsChange>, SettingsError> {
+    bundle::import_bundle(&app, bundle::read_bundle(Path::new(&path))?)
+}
@@ -1,30 +1,316 @@
-use rphonetic::{BeiderMorseBuilder, ConfigFiles, LanguageSet};
+use rphonetic::{BeiderMorse, BeiderMorseBuilder, ConfigFiles, Encoder, LanguageSet, RuleType};
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     path::PathBuf,
     sync::{Arc, Mutex},
 };
 use tauri::{AppHandle, Manager};
+use utoipa::ToSchema;
 
-pub struct Dictionary(pub Arc<Mutex<Vec<String>>>);
+/// Languages of the bundled Beider-Morse rules (`gen_languages.txt`).
+pub const LANGUAGES: &[&str] = &[
+    "arabic",
+    "cyrillic",
+    "czech",
+    "dutch",
+    "english",
+    "french",
+    "german",
+    "greek",
+    "greeklatin",
+    "hebrew",
+    "hungarian",
+    "italian",
+    "polish",
+    "portuguese",
+    "romanian",
+    "russian",
+    "spanish",
+    "turkish",
+];
+
+/// How close a transcribed word must sound to an entry to be replaced.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchStrictness {
+    /// Any shared pronunciation under the approximate rules
+    #[default]
+    Loose,
+    /// Any shared pronunciation under the exact rules, fewer false positives
+    Strict,
+    /// Only the canonical spelling and the aliases, no phonetic matching
+    Exact,
+}
+
+/// A word of the custom dictionary.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, ToSchema)]
+#[serde(default)]
+pub struct DictionaryEntry {
+    /// Spelling written in the transcription
+    pub canonical: String,
+    /// Other ways the word comes out of the model, e.g. "cube control" for "kubectl"
+    pub aliases: Vec<String>,
+    /// Languages the word is pronounced in, see `LANGUAGES`. Empty to guess from the spelling.
+    pub languages: Vec<String>,
+    /// Write `canonical` exactly as spelled, and only match aliases with the same casing
+    pub case_sensitive: bool,
+    pub strictness: MatchStrictness,
+}
+
+impl Default for DictionaryEntry {
+    fn default() -> Self {
+        Self {
+            canonical: String::new(),
+            aliases: Vec::new(),
+            // What every word was matched with before entries had languages
+            languages: vec!["french".to_string(), "english".to_string()],
+            case_sensitive: false,
+            strictness: MatchStrictness::Loose,
+        }
+    }
+}
+
+impl DictionaryEntry {
+    /// Problems with this entry, as messages naming it.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.canonical.trim().is_empty() {
+            errors.push("A dictionary entry has no spelling".to_string());
+        }
+        for language in &self.languages {
+            if !LANGUAGES.contains(&language.as_str()) {
+                errors.push(format!(
+                    "'{}': unknown language '{}'",
+                    self.canonical, language
+                ));
+            }
+        }
+        errors
+    }
+
+    /// The canonical spelling followed by the aliases.
+    fn spellings(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.canonical).chain(self.aliases.iter())
+    }
+
+    /// Whether `word` is written like `spelled`, ignoring case unless the entry cares.
+    fn same_spelling(&self, spelled: &str, word: &str) -> bool {
+        if self.case_sensitive {
+            spelled == word
+        } else {
+            spelled.to_lowercase() == word.to_lowercase()
+        }
+    }
+}
+
+/// A word of a spelling or a transcription, without the punctuation around it.
+struct Word<'a> {
+    text: &'a str,
+    /// Byte offsets of `text`
+    start: usize,
+    end: usize,
+    /// Only spaces and hyphens up to the next word, a phrase can continue over them
+    joined_to_next: bool,
+}
+
+/// Split on whitespace and hyphens, so "Jean-Baptiste" lines up with a transcribed
+/// "jean baptiste", and strip the punctuation around each word so "murmur," is read "murmur".
+fn split_words(text: &str) -> Vec<Word<'_>> {
+    let mut words: Vec<Word> = Vec::new();
+    let mut chunk_start = None;
+    for (index, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        let separator = c.is_whitespace() || c == '-';
+        match (chunk_start, separator) {
+            (None, false) => chunk_start = Some(index),
+            (Some(begin), true) => {
+                chunk_start = None;
+                let chunk = &text[begin..index];
+                // Punctuation only, such as "..." or "—"
+                let Some(offset) = chunk.find(char::is_alphanumeric) else {
+                    continue;
+                };
+                let core = chunk[offset..].trim_end_matches(|c: char| !c.is_alphanumeric());
+                let start = begin + offset;
+                let end = start + core.len();
+                if let Some(previous) = words.last_mut() {
+                    previous.joined_to_next = text[previous.end..start]
+                        .chars()
+                        .all(|c| c.is_whitespace() || c == '-');
+                }
+                words.push(Word {
+                    text: core,
+                    start,
+                    end,
+                    joined_to_next: false,
+                });
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+pub struct Dictionary(pub Arc<Mutex<Vec<DictionaryEntry>>>);
 
 impl Dictionary {
-    pub fn new(dictionary: Vec<String>) -> Self {
+    pub fn new(dictionary: Vec<DictionaryEntry>) -> Self {
         Self(Arc::new(Mutex::new(dictionary)))
     }
-    pub fn get(&self) -> Vec<String> {
+    pub fn get(&self) -> Vec<DictionaryEntry> {
         self.0.lock().unwrap().clone()
     }
-    pub fn set(&self, dictionary: Vec<String>) {
+    pub fn set(&self, dictionary: Vec<DictionaryEntry>) {
         *self.0.lock().unwrap() = dictionary;
     }
 }
 
+/// Beider-Morse encoders for both rule types, with the codes already computed.
+struct Phonetics<'a> {
+    approx: BeiderMorse<'a>,
+    exact: BeiderMorse<'a>,
+    cache: HashMap<(String, Vec<String>, MatchStrictness), Vec<String>>,
+}
+
+impl<'a> Phonetics<'a> {
+    fn new(config_files: &'a ConfigFiles) -> Self {
+        Self {
+            approx: BeiderMorseBuilder::new(config_files).build(),
+            exact: BeiderMorseBuilder::new(config_files)
+                .rule_type(RuleType::Exact)
+                .build(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Possible pronunciations of `text` in `languages`, guessed when there are none.
+    fn codes(
+        &mut self,
+        text: &str,
+        languages: &[String],
+        strictness: MatchStrictness,
+    ) -> &[String] {
+        let key = (text.to_lowercase(), languages.to_vec(), strictness);
+        let (approx, exact) = (&self.approx, &self.exact);
+        self.cache.entry(key).or_insert_with(|| {
+            let encoder = match strictness {
+                MatchStrictness::Strict => exact,
+                MatchStrictness::Loose | MatchStrictness::Exact => approx,
+            };
+            let encoded = if languages.is_empty() {
+                encoder.encode(text)
+            } else {
+                let languages =
+                    LanguageSet::from(languages.iter().map(String::as_str).collect::<Vec<_>>());
+                encoder.encode_with_languages(text, &languages)
+            };
+            encoded.split('|').map(str::to_string).collect()
+        })
+    }
+
+    /// Whether the transcribed `words` are one of the spellings of `entry`, word for word.
+    /// Punctuation around a spelling, as in ".NET" or "C++", must be around the words too, so
+    /// "net profit" is not taken for ".NET profit".
+    fn matches(&mut self, transcription: &str, words: &[Word], entry: &DictionaryEntry) -> bool {
+        let (Some(first), Some(last)) = (words.first(), words.last()) else {
+            return false;
+        };
+        entry.spellings().any(|spelling| {
+            let (leading, trailing) = surrounding_punctuation(spelling);
+            let spelled = split_words(spelling);
+            spelled.len() == words.len()
+                && transcription[..first.start].ends_with(leading)
+                && transcription[last.end..].starts_with(trailing)
+                && spelled
+                    .iter()
+                    .zip(words)
+                    .all(|(spelled, word)| self.same_word(spelled.text, word.text, entry))
+        })
+    }
+
+    fn same_word(&mut self, spelled: &str, word: &str, entry: &DictionaryEntry) -> bool {
+        if entry.same_spelling(spelled, word) {
+            return true;
+        }
+        if entry.strictness == MatchStrictness::Exact {
+            return false;
+        }
+        let candidate = self
+            .codes(word, &entry.languages, entry.strictness)
+            .to_vec();
+        self.codes(spelled, &entry.languages, entry.strictness)
+            .iter()
+            .any(|code| candidate.contains(code))
+    }
+}
+
+/// A run of transcribed words to replace with an entry.
+struct Replacement<'a> {
+    /// Index of the first word and number of words
+    first: usize,
+    len: usize,
+    entry: &'a DictionaryEntry,
+}
+
+/// `canonical` cased like the words it replaces: capitalized where they were, e.g. at the start
+/// of a sentence, and in capitals where they were shouted. Only lowercase canonicals follow the
+/// transcription, a spelling with capitals of its own like "iPhone" and case-sensitive entries
+/// are kept as is.
+fn match_case(canonical: &str, original: &str, case_sensitive: bool) -> String {
+    if case_sensitive || canonical.chars().any(char::is_uppercase) {
+        return canonical.to_string();
+    }
+    let letters: Vec<char> = original.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        return canonical.to_uppercase();
+    }
+    let mut chars = canonical.chars();
+    match (letters.first(), chars.next()) {
+        (Some(first), Some(initial)) if first.is_uppercase() => {
+            initial.to_uppercase().chain(chars).collect()
+        }
+        _ => canonical.to_string(),
+    }
+}
+
+/// Punctuation before the first and after the last letter of `spelling`, "." and "" for ".NET".
+fn surrounding_punctuation(spelling: &str) -> (&str, &str) {
+    let Some(first) = spelling.find(char::is_alphanumeric) else {
+        return ("", "");
+    };
+    let core = spelling.trim_end_matches(|c: char| !c.is_alphanumeric());
+    (&spelling[..first], &spelling[core.len()..])
+}
+
+/// Byte range of the transcription taken by `canonical` in place of `start..end`. Punctuation
+/// that is part of the spelling, as in ".NET" or "C++", replaces the same punctuation around
+/// the words instead of being added to it.
+fn replaced_range(
+    transcription: &str,
+    canonical: &str,
+    start: usize,
+    end: usize,
+) -> (usize, usize) {
+    let (leading, trailing) = surrounding_punctuation(canonical);
+    let start = if transcription[..start].ends_with(leading) {
+        start - leading.len()
+    } else {
+        start
+    };
+    let end = if transcription[end..].starts_with(trailing) {
+        end + trailing.len()
+    } else {
+        end
+    };
+    (start, end)
+}
+
 /**
  * Use phonetic algorithm to fix the transcription
  */
 pub fn fix_transcription_with_dictionary(
     transcription: String,
-    dictionary: Vec<String>,
+    dictionary: Vec<DictionaryEntry>,
     cc_rules_path: PathBuf,
 ) -> String {
     if dictionary.is_empty() {
@@ -32,37 +318,61 @@ pub fn fix_transcription_with_dictionary(
     }
 
     let config_files = ConfigFiles::new(&cc_rules_path).unwrap();
-    let builder = BeiderMorseBuilder::new(&config_files);
-    let beider_morse = builder.build();
-
-    // TODO: Make user able to choose the languages for each word
-    let langs = LanguageSet::from(vec!["french", "english"]);
-
-    // Prepare dictionary words to be encoded phonetically
-    let mut encoded_dict = Vec::new();
-    for word in &dictionary {
-        let code = beider_morse.encode_with_languages(word, &langs);
-        encoded_dict.push((word, code));
-    }
-
-    // Split transcription into words
-    let mut corrected_transcription = transcription.clone();
-    let words: Vec<&str> = transcription.split_whitespace().collect();
-
-    for word in words {
-        let candidate = beider_morse.encode_with_languages(word, &langs);
-        let candidate_codes: Vec<&str> = candidate.split('|').collect();
-        for (dict_word, dict_code) in &encoded_dict {
-            let dict_codes: Vec<&str> = dict_code.split('|').collect();
-            println!(
-                "Dict word: {:?}, Dict code: {:?}, Candidate: {:?}",
-                dict_word, dict_code, candidate
-            );
-            if dict_codes.iter().any(|dc| candidate_codes.contains(dc)) {
-                corrected_transcription = corrected_transcription.replace(word, dict_word);
+    let mut phonetics = Phonetics::new(&config_files);
+
+    let words = split_words(&transcription);
+    let longest = dictionary
+        .iter()
+        .flat_map(|entry| entry.spellings())
+        .map(|spelling| split_words(spelling).len())
+        .max()
+        .unwrap_or(1);
+
+    // Longest spans first, then from the left, so "Kubernetes operator" wins over "Kubernetes"
+    // and a word is never part of two replacements. A span doesn't cross punctuation.
+    let mut replaced = vec![false; words.len()];
+    let mut replacements = Vec::new();
+    for len in (1..=longest.min(words.len())).rev() {
+        for first in 0..=words.len() - len {
+            let span = &words[first..first + len];
+            if replaced[first..first + len].iter().any(|taken| *taken)
+                || span[..len - 1].iter().any(|word| !word.joined_to_next)
+            {
+                continue;
+            }
+            let entry = dictionary
+                .iter()
+                .find(|entry| phonetics.matches(&transcription, span, entry));
+            if let Some(entry) = entry {
+                replaced[first..first + len].fill(true);
+                replacements.push(Replacement { first, len, entry });
             }
         }
     }
+    replacements.sort_by_key(|replacement| replacement.first);
+
+    // Rebuilt from the original offsets, the text between replacements is copied untouched
+    let mut corrected_transcription = String::with_capacity(transcription.len());
+    let mut copied = 0;
+    for replacement in replacements {
+        let canonical = &replacement.entry.canonical;
+        let (start, end) = replaced_range(
+            &transcription,
+            canonical,
+            words[replacement.first].start,
+            words[replacement.first + replacement.len - 1].end,
+        );
+        // Punctuation swallowed by the previous replacement
+        let start = start.max(copied);
+        corrected_transcription.push_str(&transcription[copied..start]);
+        corrected_transcription.push_str(&match_case(
+            canonical,
+            &transcription[start..end],
+            replacement.entry.case_sensitive,
+        ));
+        copied = end;
+    }
+    corrected_transcription.push_str(&transcription[copied..]);
 
     corrected_transcription
 }
@@ -102,3 +412,263 @@ pub fn get_cc_rules_path(app_handle: &AppHandle) -> anyhow::Result<PathBuf> {
 
     anyhow::bail!("Bundled cc_rules not found in any known location");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../resources/cc-rules")
+    }
+
+    fn entry(canonical: &str, languages: &[&str], strictness: MatchStrictness) -> DictionaryEntry {
+        DictionaryEntry {
+            canonical: canonical.to_string(),
+            languages: languages.iter().map(|l| l.to_string()).collect(),
+            strictness,
+            ..Default::default()
+        }
+    }
+
+    fn alias(canonical: &str, aliases: &[&str]) -> DictionaryEntry {
+        DictionaryEntry {
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            ..entry(canonical, &[], MatchStrictness::Exact)
+        }
+    }
+
+    fn fix(text: &str, dictionary: Vec<DictionaryEntry>) -> String {
+        fix_transcription_with_dictionary(text.to_string(), dictionary, rules())
+    }
+
+    #[test]
+    fn multi_word_entry_replaces_the_whole_span() {
+        let dictionary = vec![entry(
+            "Kubernetes Operator",
+            &["english"],
+            MatchStrictness::Loose,
+        )];
+        assert_eq!(
+            fix("deploy the cubernetes operator now", dictionary),
+            "deploy the Kubernetes Operator now"
+        );
+    }
+
+    #[test]
+    fn hyphenated_entry_matches_separate_words() {
+        let dictionary = vec![entry(
+            "Jean-Baptiste Moreau",
+            &["french"],
+            MatchStrictness::Loose,
+        )];
+        assert_eq!(
+            fix("appelle jean baptiste moro demain", dictionary.clone()),
+            "appelle Jean-Baptiste Moreau demain"
+        );
+        assert_eq!(
+            fix("Jean-Baptiste moro", dictionary),
+            "Jean-Baptiste Moreau"
+        );
+    }
+
+    #[test]
+    fn part_of_a_phrase_is_left_alone() {
+        let dictionary = vec![entry(
+            "Jean-Baptiste Moreau",
+            &["french"],
+            MatchStrictness::Loose,
+        )];
+        assert_eq!(
+            fix("appelle jean baptiste demain", dictionary),
+            "appelle jean baptiste demain"
+        );
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        let dictionary = vec![
+            entry("Kubernetes", &["english"], MatchStrictness::Loose),
+            entry("Kubernetes Operator", &["english"], MatchStrictness::Loose),
+        ];
+        assert_eq!(
+            fix("deploy the cubernetes operator now", dictionary.clone()),
+            "deploy the Kubernetes Operator now"
+        );
+        assert_eq!(
+            fix("deploy the cubernetes now", dictionary),
+            "deploy the Kubernetes now"
+        );
+    }
+
+    #[test]
+    fn replacements_never_overlap() {
+        let dictionary = vec![
+            alias("VS", &["visual studio"]),
+            alias("VS Code", &["visual studio code"]),
+        ];
+        assert_eq!(
+            fix("open visual studio code and visual studio", dictionary),
+            "open VS Code and VS"
+        );
+    }
+
+    #[test]
+    fn leftmost_span_wins_between_equal_lengths() {
+        let dictionary = vec![alias("X", &["one two"]), alias("Y", &["two three"])];
+        assert_eq!(fix("one two three", dictionary), "X three");
+    }
+
+    #[test]
+    fn whitespace_around_spans_is_kept() {
+        let dictionary = vec![entry("New York", &["english"], MatchStrictness::Loose)];
+        assert_eq!(
+            fix("line one\n  new   york  rocks", dictionary),
+            "line one\n  New York  rocks"
+        );
+    }
+
+    #[test]
+    fn single_words_still_match() {
+        let dictionary = vec![entry("José", &["spanish"], MatchStrictness::Loose)];
+        assert_eq!(
+            fix("hola jose como estas", dictionary),
+            "hola José como estas"
+        );
+    }
+
+    #[test]
+    fn spanish_entries_match_spanish_pronunciation() {
+        let dictionary = vec![entry("Zaragoza", &["spanish"], MatchStrictness::Loose)];
+        assert_eq!(fix("vivo en saragosa", dictionary), "vivo en Zaragoza");
+
+        // Compared in the old default languages, the word sounds different
+        let dictionary = vec![entry(
+            "Zaragoza",
+            &["french", "english"],
+            MatchStrictness::Loose,
+        )];
+        assert_eq!(fix("vivo en saragosa", dictionary), "vivo en saragosa");
+    }
+
+    #[test]
+    fn german_entries_match_german_pronunciation() {
+        let dictionary = vec![entry("Stuttgart", &["german"], MatchStrictness::Strict)];
+        assert_eq!(
+            fix("ich wohne in schtutgart", dictionary),
+            "ich wohne in Stuttgart"
+        );
+
+        let dictionary = vec![entry(
+            "Stuttgart",
+            &["french", "english"],
+            MatchStrictness::Strict,
+        )];
+        assert_eq!(
+            fix("ich wohne in schtutgart", dictionary),
+            "ich wohne in schtutgart"
+        );
+    }
+
+    #[test]
+    fn each_entry_uses_its_own_languages() {
+        let dictionary = vec![
+            entry("Stuttgart", &["german"], MatchStrictness::Strict),
+            entry("Zaragoza", &["spanish"], MatchStrictness::Loose),
+        ];
+        assert_eq!(
+            fix("de schtutgart a saragosa", dictionary),
+            "de Stuttgart a Zaragoza"
+        );
+    }
+
+    // Regression cases: a match used to be replaced everywhere in the text, inside other words too
+
+    #[test]
+    fn short_entry_does_not_rewrite_other_words() {
+        let dictionary = vec![alias("A", &[])];
+        assert_eq!(fix("a cat and a banana", dictionary), "A cat and A banana");
+    }
+
+    #[test]
+    fn accented_neighbours_are_not_rewritten() {
+        let dictionary = vec![alias("LA", &[])];
+        assert_eq!(
+            fix("la salade est là, voilà", dictionary),
+            "LA salade est là, voilà"
+        );
+    }
+
+    #[test]
+    fn punctuation_around_a_match_is_kept() {
+        let dictionary = vec![alias("Murmure", &["murmur"])];
+        assert_eq!(
+            fix("I love murmur, really.", dictionary.clone()),
+            "I love Murmure, really."
+        );
+        assert_eq!(fix("(murmur)", dictionary.clone()), "(Murmure)");
+        assert_eq!(fix("\"murmur\"?", dictionary), "\"Murmure\"?");
+    }
+
+    #[test]
+    fn spans_do_not_cross_punctuation() {
+        let dictionary = vec![alias("New York", &[])];
+        assert_eq!(fix("it is new. york is", dictionary), "it is new. york is");
+    }
+
+    #[test]
+    fn punctuation_in_the_spelling_is_not_doubled() {
+        let dictionary = vec![alias(".NET", &[]), alias("C++", &[])];
+        assert_eq!(
+            fix("written in .net and c++.", dictionary),
+            "written in .NET and C++."
+        );
+    }
+
+    #[test]
+    fn spelling_punctuation_must_be_in_the_transcription() {
+        let dictionary = vec![alias(".NET", &[]), alias("C++", &[])];
+        assert_eq!(
+            fix("net profit is up, see plan c", dictionary.clone()),
+            "net profit is up, see plan c"
+        );
+        assert_eq!(fix("the c+ grade", dictionary), "the c+ grade");
+    }
+
+    #[test]
+    fn casing_follows_the_transcription() {
+        let dictionary = vec![alias("kubectl", &[])];
+        assert_eq!(
+            fix("Kubectl is ready", dictionary.clone()),
+            "Kubectl is ready"
+        );
+        assert_eq!(
+            fix("run KUBECTL now", dictionary.clone()),
+            "run KUBECTL now"
+        );
+        assert_eq!(fix("run Kubectl now", dictionary), "run Kubectl now");
+    }
+
+    #[test]
+    fn mixed_case_canonicals_keep_their_spelling() {
+        let dictionary = vec![alias("iPhone", &["i phone"])];
+        assert_eq!(
+            fix("I phone is here. i phone too", dictionary.clone()),
+            "iPhone is here. iPhone too"
+        );
+        assert_eq!(fix("IPHONE now", dictionary), "iPhone now");
+    }
+
+    #[test]
+    fn case_sensitive_entries_keep_their_spelling() {
+        let dictionary = vec![DictionaryEntry {
+            case_sensitive: true,
+            ..alias("iPhone", &["i phone", "IPHONE"])
+        }];
+        assert_eq!(fix("i phone is here", dictionary.clone()), "iPhone is here");
+        assert_eq!(
+            fix("my IPHONE broke", dictionary.clone()),
+            "my iPhone broke"
+        );
+        assert_eq!(fix("I Phone is here", dictionary), "I Phone is here");
+    }
+}
@@ -0,0 +1,501 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use anyhow::{Context, Result};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use once_cell::sync::Lazy;
+use parking_lot::{RwLock, RwLockReadGuard};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Start of every encrypted file, followed by the nonce and the ciphertext.
+const MAGIC: &[u8] = b"MURMURE-ENC1";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+const KEYRING_FILE: &str = "keyring.json";
+/// Written before re-encrypting, holds the id of the key the `.rekey` files are sealed with.
+const REKEY_MARKER: &str = "rekey.pending";
+const REKEY_EXTENSION: &str = "rekey";
+
+type DataKey = [u8; 32];
+
+/// Where the data key comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// Stored as is in the keyring file, unlocked at startup
+    Keyfile,
+    /// Sealed with a key derived from a passphrase, unlocked by `unlock`
+    Passphrase,
+}
+
+/// Content of `keyring.json`, encryption is enabled when the file exists.
+#[derive(Serialize, Deserialize)]
+struct Keyring {
+    /// Changes on every rotation
+    key_id: String,
+    source: KeySource,
+    /// Data key, `keyfile` source only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+    /// Argon2id salt, `passphrase` source only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    /// Data key sealed with the passphrase key, `passphrase` source only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wrapped_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RekeyMarker {
+    /// `None` when encryption is being disabled
+    key_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub source: Option<KeySource>,
+    /// False until the passphrase has been entered
+    pub unlocked: bool,
+}
+
+#[derive(Default)]
+struct KeyState {
+    source: Option<KeySource>,
+    key: Option<DataKey>,
+}
+
+static STATE: Lazy<RwLock<KeyState>> = Lazy::new(|| RwLock::new(KeyState::default()));
+
+/// Shared while the history database is used or a recording is sealed, exclusive while
+/// re-encrypting.
+static DATA_LOCK: Lazy<RwLock<()>> = Lazy::new(|| RwLock::new(()));
+
+pub type DataGuard = RwLockReadGuard<'static, ()>;
+
+/// Hold while using the history database so a key rotation cannot replace it underneath.
+pub fn data_guard() -> DataGuard {
+    // Recursive, a history write may refresh the backup while its connection is open
+    DATA_LOCK.read_recursive()
+}
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .context("Failed to resolve app data dir")?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Load the keyring and finish an interrupted re-encryption. Call before reading any data.
+pub fn init(app: &AppHandle) {
+    if let Err(e) = try_init(app) {
+        eprintln!("Failed to initialize encryption: {:#}", e);
+    }
+}
+
+fn try_init(app: &AppHandle) -> Result<()> {
+    let dir = app_data_dir(app)?;
+    let keyring = read_keyring(&dir)?;
+    recover_rekey(&dir, keyring.as_ref())?;
+
+    let mut state = STATE.write();
+    *state = KeyState::default();
+    if let Some(keyring) = keyring {
+        state.source = Some(keyring.source);
+        if keyring.source == KeySource::Keyfile {
+            state.key = Some(decode_key(keyring.key.as_deref().unwrap_or_default())?);
+        }
+    }
+    Ok(())
+}
+
+pub fn status() -> EncryptionStatus {
+    let state = STATE.read();
+    EncryptionStatus {
+        enabled: state.source.is_some(),
+        source: state.source,
+        unlocked: state.key.is_some(),
+    }
+}
+
+/// True when data is encrypted and the passphrase has not been entered yet.
+pub fn is_locked() -> bool {
+    let state = STATE.read();
+    state.source.is_some() && state.key.is_none()
+}
+
+/// Current data key, `None` when encryption is disabled.
+fn current_key() -> Result<Option<DataKey>> {
+    let state = STATE.read();
+    match (state.source, state.key) {
+        (None, _) => Ok(None),
+        (Some(_), Some(key)) => Ok(Some(key)),
+        (Some(_), None) => Err(anyhow::anyhow!(
+            "Encrypted data is locked, enter the passphrase first"
+        )),
+    }
+}
+
+fn encrypt_with(key: &DataKey, data: &[u8]) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new(&(*key).into());
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, data)
+        .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+
+    let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+fn decrypt_with(key: &DataKey, sealed: &[u8]) -> Result<Vec<u8>> {
+    let body = sealed
+        .strip_prefix(MAGIC)
+        .filter(|body| body.len() >= NONCE_LEN)
+        .ok_or_else(|| anyhow::anyhow!("Not an encrypted file"))?;
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into()?;
+    let cipher = Aes256Gcm::new(&(*key).into());
+    cipher
+        .decrypt(&nonce.into(), ciphertext)
+        .map_err(|_| anyhow::anyhow!("Decryption failed, wrong key or corrupted file"))
+}
+
+/// Encrypt `data` when encryption is enabled, return it unchanged otherwise.
+pub fn seal(data: &[u8]) -> Result<Vec<u8>> {
+    match current_key()? {
+        Some(key) => encrypt_with(&key, data),
+        None => Ok(data.to_vec()),
+    }
+}
+
+/// Decrypt `data` if it was encrypted, plain data is returned unchanged.
+pub fn unseal(data: &[u8]) -> Result<Vec<u8>> {
+    if !data.starts_with(MAGIC) {
+        return Ok(data.to_vec());
+    }
+    let key = current_key()?.ok_or_else(|| {
+        anyhow::anyhow!("File is encrypted but encryption is disabled, keyring.json is missing")
+    })?;
+    decrypt_with(&key, data)
+}
+
+pub fn read_file(path: &Path) -> Result<Vec<u8>> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    unseal(&data).with_context(|| format!("Failed to decrypt {}", path.display()))
+}
+
+pub fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    let sealed = seal(data)?;
+    crate::storage::write_atomic(path, &sealed)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Encrypt a file written in the clear, when encryption is enabled.
+pub fn seal_file(path: &Path) -> Result<()> {
+    let _guard = data_guard();
+    if current_key()?.is_none() {
+        return Ok(());
+    }
+    let data = read_file(path)?;
+    write_file(path, &data)
+}
+
+/// SQLCipher key of the history database, `None` when encryption is disabled.
+pub fn database_key() -> Result<Option<String>> {
+    Ok(current_key()?.map(|key| sqlcipher_key(&key)))
+}
+
+/// Raw key syntax understood by `PRAGMA key` and `ATTACH ... KEY`.
+fn sqlcipher_key(key: &DataKey) -> String {
+    let hex: String = key.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("x'{}'", hex)
+}
+
+fn decode_key(encoded: &str) -> Result<DataKey> {
+    let bytes = BASE64.decode(encoded).context("Invalid key encoding")?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid key length"))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<DataKey> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+fn read_keyring(dir: &Path) -> Result<Option<Keyring>> {
+    let path = dir.join(KEYRING_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)?;
+    let keyring = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(keyring))
+}
+
+fn write_keyring(dir: &Path, keyring: &Keyring) -> Result<()> {
+    let path = dir.join(KEYRING_FILE);
+    let temp_path = dir.join(format!("{}.tmp", KEYRING_FILE));
+    fs::write(&temp_path, serde_json::to_string_pretty(keyring)?)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o600))?;
+    }
+    fs::rename(&temp_path, &path)?;
+    Ok(())
+}
+
+/// Build a keyring for `key`, sealed with `passphrase` when given.
+fn new_keyring(key: &DataKey, passphrase: Option<&str>) -> Result<Keyring> {
+    let key_id = uuid::Uuid::new_v4().to_string();
+    match passphrase {
+        Some(passphrase) => {
+            if passphrase.is_empty() {
+                return Err(anyhow::anyhow!("The passphrase cannot be empty"));
+            }
+            let salt: [u8; SALT_LEN] = rand_bytes();
+            let wrapping_key = derive_key(passphrase, &salt)?;
+            Ok(Keyring {
+                key_id,
+                source: KeySource::Passphrase,
+                key: None,
+                salt: Some(BASE64.encode(salt)),
+                wrapped_key: Some(BASE64.encode(encrypt_with(&wrapping_key, key)?)),
+            })
+        }
+        None => Ok(Keyring {
+            key_id,
+            source: KeySource::Keyfile,
+            key: Some(BASE64.encode(key)),
+            salt: None,
+            wrapped_key: None,
+        }),
+    }
+}
+
+fn rand_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Unlock data encrypted with a passphrase.
+pub fn unlock(app: &AppHandle, passphrase: &str) -> Result<()> {
+    let dir = app_data_dir(app)?;
+    let keyring =
+        read_keyring(&dir)?.ok_or_else(|| anyhow::anyhow!("Encryption is not enabled"))?;
+    if keyring.source != KeySource::Passphrase {
+        return Err(anyhow::anyhow!("The key is not protected by a passphrase"));
+    }
+
+    let salt = BASE64.decode(keyring.salt.as_deref().unwrap_or_default())?;
+    let wrapped_key = BASE64.decode(keyring.wrapped_key.as_deref().unwrap_or_default())?;
+    let wrapping_key = derive_key(passphrase, &salt)?;
+    let key: DataKey = decrypt_with(&wrapping_key, &wrapped_key)
+        .map_err(|_| anyhow::anyhow!("Wrong passphrase"))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid key length"))?;
+
+    let mut state = STATE.write();
+    state.source = Some(KeySource::Passphrase);
+    state.key = Some(key);
+    Ok(())
+}
+
+/// Encrypt every file with a new key, protected by `passphrase` or kept in the keyring file.
+pub fn enable(app: &AppHandle, passphrase: Option<&str>) -> Result<()> {
+    if status().enabled {
+        return Err(anyhow::anyhow!("Encryption is already enabled"));
+    }
+    rotate(app, passphrase)
+}
+
+/// Re-encrypt every file with a new key. Also changes the passphrase, or drops it with `None`.
+pub fn rotate(app: &AppHandle, passphrase: Option<&str>) -> Result<()> {
+    let key: DataKey = rand_bytes();
+    let keyring = new_keyring(&key, passphrase)?;
+    reencrypt(app, Some((key, keyring)))
+}
+
+/// Decrypt every file and delete the keyring.
+pub fn disable(app: &AppHandle) -> Result<()> {
+    if !status().enabled {
+        return Err(anyhow::anyhow!("Encryption is not enabled"));
+    }
+    reencrypt(app, None)
+}
+
+/// Files sealed with the data key, the history database aside. Quarantined copies and
+/// leftovers of the old `history.json` hold dictations too.
+fn sealed_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && is_sealed_name(path))
+        .collect();
+    if let Ok(entries) = fs::read_dir(dir.join("recordings")) {
+        files.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && !is_rekey_file(path)),
+        );
+    }
+    files
+}
+
+fn is_sealed_name(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    matches!(
+        name,
+        "settings.json" | "settings.json.bak" | "history.json" | "history.json.migrated"
+    ) || name.ends_with(".json.invalid")
+        || name.contains(".corrupt-")
+}
+
+fn rekey_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".");
+    name.push(REKEY_EXTENSION);
+    PathBuf::from(name)
+}
+
+fn is_rekey_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == REKEY_EXTENSION)
+}
+
+/// Write a copy of every file under the new key, then switch over by replacing the keyring.
+/// Until the keyring is replaced the old files stay untouched, see `recover_rekey`.
+fn reencrypt(app: &AppHandle, new: Option<(DataKey, Keyring)>) -> Result<()> {
+    // The file being recorded is still open for writing
+    if crate::audio::is_recording() {
+        return Err(anyhow::anyhow!("Stop the recording first"));
+    }
+    // Settings and history writes wait, a change made now would be lost when the re-encrypted
+    // copies replace the files. History first, its writers may read the settings.
+    let _history = DATA_LOCK.write();
+    let _settings = crate::settings::lock_settings();
+    let dir = app_data_dir(app)?;
+    let new_key = new.as_ref().map(|(key, _)| *key);
+    let marker = RekeyMarker {
+        key_id: new.as_ref().map(|(_, keyring)| keyring.key_id.clone()),
+    };
+    fs::write(dir.join(REKEY_MARKER), serde_json::to_string(&marker)?)?;
+
+    let result = (|| -> Result<()> {
+        for path in sealed_files(&dir) {
+            let data = match read_file(&path) {
+                Ok(data) => data,
+                // Damaged beyond decryption, it cannot be leaked either
+                Err(e) if path.to_string_lossy().contains(".corrupt-") => {
+                    eprintln!("Leaving {} as is: {:#}", path.display(), e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let rewritten = match &new_key {
+                Some(key) => encrypt_with(key, &data)?,
+                None => data,
+            };
+            fs::write(rekey_path(&path), rewritten)?;
+        }
+        let database = dir.join(crate::history::DATABASE_FILE);
+        crate::history::export_database(
+            app,
+            &rekey_path(&database),
+            new_key.as_ref().map(sqlcipher_key).as_deref(),
+        )?;
+        // The backup must stay readable with the new key, the current database is a fine one
+        fs::copy(
+            rekey_path(&database),
+            rekey_path(&crate::storage::backup_path(&database)),
+        )?;
+        Ok(())
+    })();
+    if let Err(e) = result {
+        discard_rekey_files(&dir);
+        let _ = fs::remove_file(dir.join(REKEY_MARKER));
+        return Err(e);
+    }
+
+    // Point of no return, the new files become the current ones
+    match &new {
+        Some((_, keyring)) => write_keyring(&dir, keyring)?,
+        None => fs::remove_file(dir.join(KEYRING_FILE))?,
+    }
+    {
+        let mut state = STATE.write();
+        state.source = new.as_ref().map(|(_, keyring)| keyring.source);
+        state.key = new_key;
+    }
+    commit_rekey_files(&dir)?;
+    fs::remove_file(dir.join(REKEY_MARKER))?;
+    Ok(())
+}
+
+fn rekey_files(dir: &Path) -> Vec<PathBuf> {
+    [dir.to_path_buf(), dir.join("recordings")]
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter(|path| is_rekey_file(path))
+        .collect()
+}
+
+fn commit_rekey_files(dir: &Path) -> Result<()> {
+    for path in rekey_files(dir) {
+        fs::rename(&path, path.with_extension(""))?;
+    }
+    Ok(())
+}
+
+fn discard_rekey_files(dir: &Path) {
+    for path in rekey_files(dir) {
+        if let Err(e) = fs::remove_file(&path) {
+            eprintln!("Failed to delete {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Finish or roll back a re-encryption interrupted by a crash, depending on whether the
+/// keyring had already been replaced.
+fn recover_rekey(dir: &Path, keyring: Option<&Keyring>) -> Result<()> {
+    let marker_path = dir.join(REKEY_MARKER);
+    if !marker_path.exists() {
+        return Ok(());
+    }
+
+    let committed = match serde_json::from_str::<RekeyMarker>(&fs::read_to_string(&marker_path)?) {
+        Ok(marker) => marker.key_id.as_deref() == keyring.map(|keyring| keyring.key_id.as_str()),
+        Err(e) => {
+            eprintln!("Invalid {}: {}", marker_path.display(), e);
+            false
+        }
+    };
+    if committed {
+        println!("Finishing interrupted re-encryption");
+        commit_rekey_files(dir)?;
+    } else {
+        println!("Rolling back interrupted re-encryption");
+        discard_rekey_files(dir);
+    }
+    fs::remove_file(&marker_path)?;
+    Ok(())
+}
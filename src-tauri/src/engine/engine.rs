@@ -12,7 +12,6 @@ use std::path::{Path, PathBuf};
 /// Controls the level of detail in the timing information returned
 /// by the Parakeet engine.
 #[derive(Debug, Clone, Default, PartialEq)]
-#[allow(dead_code)] // Word/Segment will be useful for UI timestamps
 pub enum TimestampGranularity {
     /// Token-level timestamps (most detailed, default)
     #[default]
@@ -142,6 +141,32 @@ impl ParakeetEngine {
             model: None,
         }
     }
+
+    /// Transcribe audio samples once and convert the timestamps to several granularities.
+    ///
+    /// Inference only runs a single time, which makes this cheaper than calling
+    /// `transcribe_samples()` once per granularity. Results are returned in the
+    /// same order as `granularities`.
+    pub fn transcribe_samples_with_granularities(
+        &mut self,
+        samples: Vec<f32>,
+        granularities: &[TimestampGranularity],
+    ) -> Result<Vec<TranscriptionResult>, Box<dyn std::error::Error>> {
+        let model: &mut ParakeetModel = self
+            .model
+            .as_mut()
+            .ok_or("Model not loaded. Call load_model() first.")?;
+
+        let timestamped_result = model.transcribe_samples(samples)?;
+
+        Ok(granularities
+            .iter()
+            .map(|granularity| TranscriptionResult {
+                text: timestamped_result.text.clone(),
+                segments: convert_timestamps(&timestamped_result, granularity.clone()),
+            })
+            .collect())
+    }
 }
 
 impl Drop for ParakeetEngine {
@@ -8,7 +8,6 @@ use std::path::Path;
 /// Contains both the full transcribed text and detailed timing information
 /// for individual segments within the audio.
 #[derive(Debug)]
-#[allow(dead_code)] // segments will be useful for UI timestamps
 pub struct TranscriptionResult {
     /// The complete transcribed text from the audio
     pub text: String,
@@ -21,7 +20,6 @@ pub struct TranscriptionResult {
 /// Represents a portion of the transcribed audio with start and end timestamps
 /// and the corresponding text content.
 #[derive(Debug)]
-#[allow(dead_code)] // fields will be useful for UI timestamps
 pub struct TranscriptionSegment {
     /// Start time of the segment in seconds
     pub start: f32,
@@ -1,91 +1,869 @@
+use crate::storage::{self, Recovery};
 use anyhow::Result;
+use chrono::{Local, TimeZone};
+use rusqlite::{params, params_from_iter, Connection, ErrorCode, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
 
-const MAX_HISTORY_ENTRIES: usize = 5;
+/// SQLite database in the app data directory, encrypted with SQLCipher when encryption is enabled.
+pub const DATABASE_FILE: &str = "history.db";
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Number of entries shown in the home screen.
+const RECENT_ENTRIES: usize = 5;
+
+/// How often `history.db.bak` is refreshed.
+const BACKUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+static INTEGRITY_CHECKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct HistoryEntry {
     pub id: u64,
     pub timestamp: i64,
+    /// Final text, after dictionary correction
     pub text: String,
+    #[serde(flatten)]
+    pub metadata: TranscriptionMetadata,
+    /// File name of the kept recording in the recordings directory, if any
+    #[serde(default)]
+    pub audio_file: Option<String>,
+}
+
+/// Where a dictation came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistorySource {
+    Shortcut,
+    HttpApi,
+    FileImport,
+}
+
+impl HistorySource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HistorySource::Shortcut => "shortcut",
+            HistorySource::HttpApi => "http_api",
+            HistorySource::FileImport => "file_import",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "shortcut" => Some(HistorySource::Shortcut),
+            "http_api" => Some(HistorySource::HttpApi),
+            "file_import" => Some(HistorySource::FileImport),
+            _ => None,
+        }
+    }
+}
+
+/// How a dictation was produced. Fields are `None` for entries recorded before they were tracked.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(default)]
+pub struct TranscriptionMetadata {
+    /// Text returned by the model, before dictionary correction
+    pub raw_text: Option<String>,
+    /// Length of the audio, in seconds
+    pub duration: Option<f64>,
+    pub model: Option<String>,
+    pub quantization: Option<String>,
+    /// Time spent in the engine, in milliseconds
+    pub inference_ms: Option<u64>,
+    /// Inference time divided by audio duration, below 1 is faster than real time
+    pub real_time_factor: Option<f64>,
+    pub source: Option<HistorySource>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct HistoryData {
-    entries: Vec<HistoryEntry>,
-    next_id: u64,
+/// Layout of the `history.json` file used before the SQLite store, kept for JSON exports.
+#[derive(Serialize, Deserialize)]
+pub struct HistoryData {
+    pub entries: Vec<HistoryEntry>,
+    #[serde(default)]
+    pub next_id: u64,
 }
 
-impl Default for HistoryData {
-    fn default() -> Self {
-        Self {
-            entries: Vec::new(),
-            next_id: 1,
+/// Accepted by `import_transcriptions`: a JSON export, an old `history.json`, or a bare list of entries.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum HistoryImport {
+    Data(HistoryData),
+    Entries(Vec<HistoryEntry>),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
         }
     }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, utoipa::ToSchema)]
+pub struct ImportSummary {
+    /// Entries added to the history, under new ids
+    pub imported: usize,
+    /// Entries already present, same timestamp and text
+    pub skipped: usize,
+}
+
+/// Filters for `search_transcriptions`, every field is optional.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct HistoryFilter {
+    /// Full-text query, every word must appear in the entry
+    pub query: Option<String>,
+    /// Inclusive lower bound, in seconds since the Unix epoch
+    pub from: Option<i64>,
+    /// Inclusive upper bound, in seconds since the Unix epoch
+    pub to: Option<i64>,
 }
 
-fn get_history_file_path(app: &AppHandle) -> Result<PathBuf> {
+/// Each step brings the schema from version `index` to `index + 1`.
+const MIGRATIONS: &[&str] = &[
+    // 1: entries with a full-text index kept in sync by triggers
+    "CREATE TABLE history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp INTEGER NOT NULL,
+        text TEXT NOT NULL
+    );
+    CREATE INDEX history_timestamp ON history(timestamp);
+    CREATE VIRTUAL TABLE history_fts USING fts5(text, content='history', content_rowid='id');
+    CREATE TRIGGER history_ai AFTER INSERT ON history BEGIN
+        INSERT INTO history_fts(rowid, text) VALUES (new.id, new.text);
+    END;
+    CREATE TRIGGER history_ad AFTER DELETE ON history BEGIN
+        INSERT INTO history_fts(history_fts, rowid, text) VALUES ('delete', old.id, old.text);
+    END;
+    CREATE TRIGGER history_au AFTER UPDATE OF text ON history BEGIN
+        INSERT INTO history_fts(history_fts, rowid, text) VALUES ('delete', old.id, old.text);
+        INSERT INTO history_fts(rowid, text) VALUES (new.id, new.text);
+    END;",
+    // 2: how each dictation was produced
+    "ALTER TABLE history ADD COLUMN raw_text TEXT;
+    ALTER TABLE history ADD COLUMN duration REAL;
+    ALTER TABLE history ADD COLUMN model TEXT;
+    ALTER TABLE history ADD COLUMN quantization TEXT;
+    ALTER TABLE history ADD COLUMN inference_ms INTEGER;
+    ALTER TABLE history ADD COLUMN real_time_factor REAL;
+    ALTER TABLE history ADD COLUMN source TEXT;",
+    // 3: recording kept alongside the entry
+    "ALTER TABLE history ADD COLUMN audio_file TEXT;",
+];
+
+/// Columns read by `row_to_entry`, in order.
+const COLUMNS: &str =
+    "id, timestamp, text, raw_text, duration, model, quantization, inference_ms, real_time_factor, source, audio_file";
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf> {
     let app_data_dir = app.path().app_data_dir()?;
     if !app_data_dir.exists() {
         fs::create_dir_all(&app_data_dir)?;
     }
-    Ok(app_data_dir.join("history.json"))
+    Ok(app_data_dir)
+}
+
+/// A connection to the history database. Key rotation waits until it is closed, so no write
+/// lands in a database about to be replaced by its re-encrypted copy.
+struct HistoryConnection {
+    conn: Connection,
+    _guard: crate::encryption::DataGuard,
+}
+
+impl Deref for HistoryConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
 }
 
-fn read_history(app: &AppHandle) -> Result<HistoryData> {
-    let path = get_history_file_path(app)?;
+impl DerefMut for HistoryConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        &mut self.conn
+    }
+}
+
+fn open(app: &AppHandle) -> Result<HistoryConnection> {
+    let guard = crate::encryption::data_guard();
+    Ok(HistoryConnection {
+        conn: open_connection(app)?,
+        _guard: guard,
+    })
+}
+
+fn open_connection(app: &AppHandle) -> Result<Connection> {
+    let dir = app_data_dir(app)?;
+    let path = dir.join(DATABASE_FILE);
+    let key = crate::encryption::database_key()?;
+    let mut conn = match open_database(&path, key.as_deref()) {
+        Err(e) if is_corruption(&e) => recover_database(app, &path, key.as_deref(), &e)?,
+        result => result?,
+    };
+    import_legacy_json(&mut conn, &dir.join("history.json"))?;
+    Ok(conn)
+}
+
+fn open_database(path: &Path, key: Option<&str>) -> Result<Connection> {
+    let mut conn = Connection::open(path)?;
+    if let Some(key) = key {
+        conn.execute_batch(&format!("PRAGMA key = \"{}\";", key))?;
+    }
+    conn.busy_timeout(Duration::from_secs(5))?;
+    // A full check is too slow for every open, once per run catches damage from a previous crash
+    if !INTEGRITY_CHECKED.swap(true, Ordering::SeqCst) {
+        let result: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+        if result != "ok" {
+            INTEGRITY_CHECKED.store(false, Ordering::SeqCst);
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CORRUPT),
+                Some(result),
+            )
+            .into());
+        }
+    }
+    migrate(&mut conn)?;
+    Ok(conn)
+}
+
+fn is_corruption(error: &anyhow::Error) -> bool {
+    matches!(
+        error
+            .downcast_ref::<rusqlite::Error>()
+            .and_then(|e| e.sqlite_error_code()),
+        Some(ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase)
+    )
+}
+
+/// Move the damaged database aside and continue from the backup, or from an empty history.
+fn recover_database(
+    app: &AppHandle,
+    path: &Path,
+    key: Option<&str>,
+    error: &anyhow::Error,
+) -> Result<Connection> {
+    eprintln!("History database is damaged: {:#}", error);
+    let quarantined = storage::quarantine(path)?;
+    let backup = storage::backup_path(path);
+
+    let mut restored_from_backup = false;
+    if backup.exists() {
+        fs::copy(&backup, path)?;
+        match open_database(path, key) {
+            Ok(_) => restored_from_backup = true,
+            Err(e) => {
+                eprintln!("History backup is unusable too: {:#}", e);
+                fs::remove_file(path)?;
+            }
+        }
+    }
+    let conn = open_database(path, key)?;
+
+    storage::report_recovery(
+        app,
+        Recovery {
+            file: DATABASE_FILE.to_string(),
+            quarantined: Some(quarantined.to_string_lossy().to_string()),
+            restored_from_backup,
+        },
+    );
+    Ok(conn)
+}
+
+/// Refresh `history.db.bak` when it is older than `BACKUP_INTERVAL`.
+fn backup_database(app: &AppHandle) -> Result<()> {
+    let _guard = crate::encryption::data_guard();
+    let path = app_data_dir(app)?.join(DATABASE_FILE);
+    let backup = storage::backup_path(&path);
+    let fresh = fs::metadata(&backup)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < BACKUP_INTERVAL);
+    if fresh {
+        return Ok(());
+    }
+
+    // Export next to the backup first, so a crash never leaves a half-written backup
+    let temp = storage::with_suffix(&backup, ".tmp");
+    let key = crate::encryption::database_key()?;
+    export_database(app, &temp, key.as_deref())?;
+    fs::rename(&temp, &backup)?;
+    Ok(())
+}
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Move the entries of the old `history.json` into the database, once.
+fn import_legacy_json(conn: &mut Connection, path: &Path) -> Result<()> {
     if !path.exists() {
-        return Ok(HistoryData::default());
+        return Ok(());
     }
-    let content = fs::read_to_string(path)?;
-    let data = serde_json::from_str(&content)?;
-    Ok(data)
+
+    // Sealed like the other files once encryption is enabled
+    let content = crate::encryption::read_file(path)?;
+    let data: HistoryData = match serde_json::from_slice(&content) {
+        Ok(data) => data,
+        Err(e) => {
+            // Keep the file around for manual recovery, but stop trying on every open
+            eprintln!("Failed to parse {}: {}", path.display(), e);
+            fs::rename(path, path.with_extension("json.invalid"))?;
+            return Ok(());
+        }
+    };
+    let tx = conn.transaction()?;
+    for entry in &data.entries {
+        insert(&tx, Some(entry.id), entry.timestamp, &entry.text, &entry.metadata)?;
+    }
+    tx.commit()?;
+
+    fs::rename(path, path.with_extension("json.migrated"))?;
+    println!(
+        "Migrated {} history entries from {}",
+        data.entries.len(),
+        path.display()
+    );
+    Ok(())
 }
 
-fn write_history(app: &AppHandle, data: &HistoryData) -> Result<()> {
-    let path = get_history_file_path(app)?;
-    let content = serde_json::to_string_pretty(data)?;
-    fs::write(path, content)?;
+/// Insert an entry, keeping `id` when given. Existing ids are left untouched.
+fn insert(
+    conn: &Connection,
+    id: Option<u64>,
+    timestamp: i64,
+    text: &str,
+    metadata: &TranscriptionMetadata,
+) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO history
+            (id, timestamp, text, raw_text, duration, model, quantization, inference_ms, real_time_factor, source)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            id.map(|id| id as i64),
+            timestamp,
+            text,
+            metadata.raw_text,
+            metadata.duration,
+            metadata.model,
+            metadata.quantization,
+            metadata.inference_ms.map(|ms| ms as i64),
+            metadata.real_time_factor,
+            metadata.source.map(|source| source.as_str()),
+        ],
+    )?;
     Ok(())
 }
 
-pub fn add_transcription(app: &AppHandle, text: String) -> Result<()> {
-    let mut data = read_history(app)?;
+fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get::<_, i64>(0)? as u64,
+        timestamp: row.get(1)?,
+        text: row.get(2)?,
+        metadata: TranscriptionMetadata {
+            raw_text: row.get(3)?,
+            duration: row.get(4)?,
+            model: row.get(5)?,
+            quantization: row.get(6)?,
+            inference_ms: row.get::<_, Option<i64>>(7)?.map(|ms| ms as u64),
+            real_time_factor: row.get(8)?,
+            source: row
+                .get::<_, Option<String>>(9)?
+                .as_deref()
+                .and_then(HistorySource::parse),
+        },
+        audio_file: row.get(10)?,
+    })
+}
 
-    let timestamp = std::time::SystemTime::now()
+/// Turn free text into an FTS5 query: every word must match, the last one as a prefix.
+fn fts_query(query: &str) -> Option<String> {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", words.join(" ")))
+}
+
+/// Drop entries older than the retention period or beyond the maximum count.
+fn apply_retention(app: &AppHandle, conn: &Connection) -> Result<()> {
+    let s = crate::settings::load_settings(app);
+    if s.history_retention_days > 0 {
+        let cutoff = now_secs()? - s.history_retention_days as i64 * 24 * 60 * 60;
+        conn.execute("DELETE FROM history WHERE timestamp < ?1", params![cutoff])?;
+    }
+    if s.history_max_entries > 0 {
+        conn.execute(
+            "DELETE FROM history WHERE id NOT IN (
+                SELECT id FROM history ORDER BY timestamp DESC, id DESC LIMIT ?1
+            )",
+            params![s.history_max_entries as i64],
+        )?;
+    }
+    Ok(())
+}
+
+fn now_secs() -> Result<i64> {
+    Ok(std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)?
-        .as_secs() as i64;
+        .as_secs() as i64)
+}
+
+pub fn add_transcription(
+    app: &AppHandle,
+    text: String,
+    metadata: TranscriptionMetadata,
+) -> Result<HistoryEntry> {
+    let conn = open(app)?;
+    let timestamp = now_secs()?;
 
+    insert(&conn, None, timestamp, &text, &metadata)?;
     let entry = HistoryEntry {
-        id: data.next_id,
+        id: conn.last_insert_rowid() as u64,
         timestamp,
         text,
+        metadata,
+        audio_file: None,
     };
 
-    data.entries.insert(0, entry);
-    data.next_id += 1;
+    apply_retention(app, &conn)?;
+    drop(conn);
 
-    if data.entries.len() > MAX_HISTORY_ENTRIES {
-        data.entries.truncate(MAX_HISTORY_ENTRIES);
+    if let Err(e) = backup_database(app) {
+        eprintln!("Failed to back up history: {:#}", e);
     }
 
-    write_history(app, &data)?;
-
     let _ = app.emit("history-updated", ());
 
-    Ok(())
+    Ok(entry)
 }
 
 pub fn get_recent_transcriptions(app: &AppHandle) -> Result<Vec<HistoryEntry>> {
-    let data = read_history(app)?;
-    Ok(data.entries)
+    let (entries, _) = get_transcriptions_page(app, 0, RECENT_ENTRIES)?;
+    Ok(entries)
 }
 
 pub fn get_last_transcription(app: &AppHandle) -> Result<String> {
-    let data = read_history(app)?;
-    Ok(data.entries.first().unwrap().text.clone())
+    get_latest_transcription(app)?
+        .map(|entry| entry.text)
+        .ok_or_else(|| anyhow::anyhow!("History is empty"))
+}
+
+pub fn get_latest_transcription(app: &AppHandle) -> Result<Option<HistoryEntry>> {
+    let (entries, _) = get_transcriptions_page(app, 0, 1)?;
+    Ok(entries.into_iter().next())
+}
+
+/// Return a page of the history, most recent first, along with the total number of entries.
+pub fn get_transcriptions_page(
+    app: &AppHandle,
+    offset: usize,
+    limit: usize,
+) -> Result<(Vec<HistoryEntry>, usize)> {
+    search_transcriptions(app, &HistoryFilter::default(), offset, limit)
+}
+
+/// Return a page of the entries matching `filter`, most recent first, along with the number of matches.
+pub fn search_transcriptions(
+    app: &AppHandle,
+    filter: &HistoryFilter,
+    offset: usize,
+    limit: usize,
+) -> Result<(Vec<HistoryEntry>, usize)> {
+    let conn = open(app)?;
+    let (where_clause, mut values) = where_clause(filter);
+
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM history {}", where_clause),
+        params_from_iter(values.iter()),
+        |row| row.get(0),
+    )?;
+
+    values.push((limit as i64).into());
+    values.push((offset as i64).into());
+    let mut statement = conn.prepare(&format!(
+        "SELECT {} FROM history {} ORDER BY timestamp DESC, id DESC LIMIT ? OFFSET ?",
+        COLUMNS, where_clause
+    ))?;
+    let entries = statement
+        .query_map(params_from_iter(values.iter()), row_to_entry)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok((entries, total as usize))
+}
+
+/// SQL condition and its parameters for `filter`, empty when nothing is filtered.
+fn where_clause(filter: &HistoryFilter) -> (String, Vec<rusqlite::types::Value>) {
+    let mut conditions = Vec::new();
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    if let Some(query) = filter.query.as_deref().and_then(fts_query) {
+        conditions.push("id IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)");
+        values.push(query.into());
+    }
+    if let Some(from) = filter.from {
+        conditions.push("timestamp >= ?");
+        values.push(from.into());
+    }
+    if let Some(to) = filter.to {
+        conditions.push("timestamp <= ?");
+        values.push(to.into());
+    }
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+    (where_clause, values)
+}
+
+/// Render the entries matching `filter`, oldest first, in `format`.
+pub fn export_transcriptions(
+    app: &AppHandle,
+    filter: &HistoryFilter,
+    format: ExportFormat,
+) -> Result<String> {
+    let conn = open(app)?;
+    let (where_clause, values) = where_clause(filter);
+    let mut statement = conn.prepare(&format!(
+        "SELECT {} FROM history {} ORDER BY timestamp, id",
+        COLUMNS, where_clause
+    ))?;
+    let entries = statement
+        .query_map(params_from_iter(values.iter()), row_to_entry)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    match format {
+        ExportFormat::Json => {
+            let next_id = entries.iter().map(|entry| entry.id + 1).max().unwrap_or(1);
+            Ok(serde_json::to_string_pretty(&HistoryData { entries, next_id })?)
+        }
+        ExportFormat::Csv => Ok(export_csv(&entries)),
+        ExportFormat::Markdown => Ok(export_markdown(&entries)),
+    }
+}
+
+/// Local date and time of a timestamp, falls back to the raw value when out of range.
+fn format_timestamp(timestamp: i64) -> String {
+    match Local.timestamp_opt(timestamp, 0).single() {
+        Some(date) => date.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn export_csv(entries: &[HistoryEntry]) -> String {
+    let mut csv = String::from(
+        "id,timestamp,date,text,raw_text,duration,model,quantization,inference_ms,real_time_factor,source\r\n",
+    );
+    for entry in entries {
+        let metadata = &entry.metadata;
+        let fields = [
+            entry.id.to_string(),
+            entry.timestamp.to_string(),
+            format_timestamp(entry.timestamp),
+            entry.text.clone(),
+            metadata.raw_text.clone().unwrap_or_default(),
+            metadata.duration.map(|d| d.to_string()).unwrap_or_default(),
+            metadata.model.clone().unwrap_or_default(),
+            metadata.quantization.clone().unwrap_or_default(),
+            metadata.inference_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+            metadata.real_time_factor.map(|rtf| rtf.to_string()).unwrap_or_default(),
+            metadata.source.map(|source| source.as_str().to_string()).unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn export_markdown(entries: &[HistoryEntry]) -> String {
+    let mut markdown = String::from("# Murmure history\n");
+    for entry in entries {
+        markdown.push_str(&format!(
+            "\n## {}\n\n{}\n",
+            format_timestamp(entry.timestamp),
+            entry.text.trim()
+        ));
+    }
+    markdown
+}
+
+/// Add entries from another machine under new ids, skipping those already in the history.
+pub fn import_transcriptions(app: &AppHandle, data: HistoryImport) -> Result<ImportSummary> {
+    let entries = match data {
+        HistoryImport::Data(data) => data.entries,
+        HistoryImport::Entries(entries) => entries,
+    };
+
+    let mut conn = open(app)?;
+    let tx = conn.transaction()?;
+    let mut summary = ImportSummary {
+        imported: 0,
+        skipped: 0,
+    };
+    for entry in &entries {
+        let exists = tx
+            .query_row(
+                "SELECT 1 FROM history WHERE timestamp = ?1 AND text = ?2",
+                params![entry.timestamp, entry.text],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if exists {
+            summary.skipped += 1;
+            continue;
+        }
+        // Recordings stay on the machine they were made on
+        insert(&tx, None, entry.timestamp, &entry.text, &entry.metadata)?;
+        summary.imported += 1;
+    }
+    tx.commit()?;
+
+    apply_retention(app, &conn)?;
+
+    let _ = app.emit("history-updated", ());
+
+    Ok(summary)
+}
+
+pub fn get_transcription(app: &AppHandle, id: u64) -> Result<Option<HistoryEntry>> {
+    let conn = open(app)?;
+    let entry = conn
+        .query_row(
+            &format!("SELECT {} FROM history WHERE id = ?1", COLUMNS),
+            params![id as i64],
+            row_to_entry,
+        )
+        .optional()?;
+    Ok(entry)
+}
+
+/// Delete an entry and its kept recording, returns `false` when no entry has this id.
+pub fn delete_transcription(app: &AppHandle, id: u64) -> Result<bool> {
+    let conn = open(app)?;
+    let recordings_dir = app_data_dir(app)?.join("recordings");
+    if !delete_entry(&conn, &recordings_dir, id)? {
+        return Ok(false);
+    }
+
+    let _ = app.emit("history-updated", ());
+
+    Ok(true)
+}
+
+/// The recording goes first, so a failure to delete it leaves the entry in place.
+fn delete_entry(conn: &Connection, recordings_dir: &Path, id: u64) -> Result<bool> {
+    let audio_file: Option<Option<String>> = conn
+        .query_row(
+            "SELECT audio_file FROM history WHERE id = ?1",
+            params![id as i64],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(audio_file) = audio_file else {
+        return Ok(false);
+    };
+
+    if let Some(audio_file) = audio_file {
+        let shared: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM history WHERE audio_file = ?1 AND id != ?2)",
+            params![audio_file, id as i64],
+            |row| row.get(0),
+        )?;
+        if !shared {
+            match fs::remove_file(recordings_dir.join(&audio_file)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+    }
+
+    conn.execute("DELETE FROM history WHERE id = ?1", params![id as i64])?;
+    Ok(true)
+}
+
+/// Replace the text and metadata of an entry, returns `None` when no entry has this id.
+pub fn update_transcription(
+    app: &AppHandle,
+    id: u64,
+    text: String,
+    metadata: TranscriptionMetadata,
+) -> Result<Option<HistoryEntry>> {
+    let conn = open(app)?;
+    let updated = conn.execute(
+        "UPDATE history SET text = ?2, raw_text = ?3, duration = ?4, model = ?5, quantization = ?6,
+            inference_ms = ?7, real_time_factor = ?8, source = ?9
+        WHERE id = ?1",
+        params![
+            id as i64,
+            text,
+            metadata.raw_text,
+            metadata.duration,
+            metadata.model,
+            metadata.quantization,
+            metadata.inference_ms.map(|ms| ms as i64),
+            metadata.real_time_factor,
+            metadata.source.map(|source| source.as_str()),
+        ],
+    )?;
+    if updated == 0 {
+        return Ok(None);
+    }
+
+    let _ = app.emit("history-updated", ());
+
+    get_transcription(app, id)
+}
+
+/// Link a kept recording to an entry, or unlink it with `None`.
+pub fn set_audio_file(app: &AppHandle, id: u64, audio_file: Option<&str>) -> Result<()> {
+    let conn = open(app)?;
+    conn.execute(
+        "UPDATE history SET audio_file = ?2 WHERE id = ?1",
+        params![id as i64, audio_file],
+    )?;
+    Ok(())
+}
+
+/// Unlink a recording from every entry, once its file has been deleted.
+pub fn forget_audio_file(app: &AppHandle, audio_file: &str) -> Result<()> {
+    let conn = open(app)?;
+    conn.execute(
+        "UPDATE history SET audio_file = NULL WHERE audio_file = ?1",
+        params![audio_file],
+    )?;
+    Ok(())
+}
+
+/// File names of every recording still linked to an entry.
+pub fn audio_files(app: &AppHandle) -> Result<HashSet<String>> {
+    let conn = open(app)?;
+    let mut statement =
+        conn.prepare("SELECT DISTINCT audio_file FROM history WHERE audio_file IS NOT NULL")?;
+    let files = statement
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<HashSet<String>>>()?;
+    Ok(files)
+}
+
+/// Copy the whole database to `target`, encrypted with `key`, or in the clear with `None`.
+/// Callers hold the data lock, shared or exclusive, see `encryption::data_guard`.
+pub fn export_database(app: &AppHandle, target: &Path, key: Option<&str>) -> Result<()> {
+    if target.exists() {
+        fs::remove_file(target)?;
+    }
+    let conn = open_connection(app)?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS rekeyed KEY ?2",
+        params![target.to_string_lossy(), key.unwrap_or_default()],
+    )?;
+    conn.query_row("SELECT sqlcipher_export('rekeyed')", [], |_| Ok(()))?;
+    // Not copied by sqlcipher_export
+    conn.execute_batch(&format!(
+        "PRAGMA rekeyed.user_version = {}; DETACH DATABASE rekeyed;",
+        MIGRATIONS.len()
+    ))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    fn insert(conn: &Connection, audio_file: Option<&str>) -> u64 {
+        conn.execute(
+            "INSERT INTO history (timestamp, text, audio_file) VALUES (0, 'hello', ?1)",
+            params![audio_file],
+        )
+        .unwrap();
+        conn.last_insert_rowid() as u64
+    }
+
+    fn recordings_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("murmure-history-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn deleting_an_entry_deletes_its_recording() {
+        let conn = database();
+        let dir = recordings_dir();
+        fs::write(dir.join("kept.wav"), b"RIFF").unwrap();
+        fs::write(dir.join("other.wav"), b"RIFF").unwrap();
+        let id = insert(&conn, Some("kept.wav"));
+        insert(&conn, Some("other.wav"));
+
+        assert!(delete_entry(&conn, &dir, id).unwrap());
+        assert!(!dir.join("kept.wav").exists());
+        assert!(dir.join("other.wav").exists());
+        assert!(!delete_entry(&conn, &dir, id).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_missing_recording_does_not_block_the_delete() {
+        let conn = database();
+        let dir = recordings_dir();
+        let id = insert(&conn, Some("already-gone.wav"));
+        let without_recording = insert(&conn, None);
+
+        assert!(delete_entry(&conn, &dir, id).unwrap());
+        assert!(delete_entry(&conn, &dir, without_recording).unwrap());
+        let left: i64 = conn
+            .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(left, 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,105 @@
+use super::error::ApiError;
+use crate::settings;
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Query parameter accepted in place of the `Authorization` header, for
+/// WebSocket and EventSource clients that cannot set headers.
+const TOKEN_QUERY_PARAM: &str = "access_token";
+
+fn bearer_token(request: &Request) -> Option<String> {
+    if let Some(value) = request.headers().get(header::AUTHORIZATION) {
+        let value = value.to_str().ok()?;
+        let (scheme, token) = value.split_once(' ')?;
+        if scheme.eq_ignore_ascii_case("bearer") {
+            return Some(token.trim().to_string());
+        }
+        return None;
+    }
+
+    // Percent-decoded, keys may contain reserved characters
+    let Query(mut query) = Query::<HashMap<String, String>>::try_from_uri(request.uri()).ok()?;
+    query.remove(TOKEN_QUERY_PARAM)
+}
+
+/// Compare in constant time so the key cannot be guessed from response timings.
+fn keys_match(expected: &str, provided: &str) -> bool {
+    let (expected, provided) = (expected.as_bytes(), provided.as_bytes());
+    if expected.len() != provided.len() {
+        return false;
+    }
+    expected
+        .iter()
+        .zip(provided)
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+fn unauthorized(path: &str, message: &str) -> Response {
+    if !path.starts_with("/v1/") {
+        return ApiError::Unauthorized(message.to_string()).into_response();
+    }
+
+    // OpenAI clients expect their own error shape
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        Json(serde_json::json!({
+            "error": {
+                "message": message,
+                "type": "invalid_request_error",
+                "param": null,
+                "code": "invalid_api_key",
+            }
+        })),
+    )
+        .into_response()
+}
+
+/// Reject requests that do not carry the API key from the settings.
+pub async fn require_api_key(
+    State(app): State<Arc<tauri::AppHandle>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let expected = settings::load_settings(&app).api_key;
+    let path = request.uri().path().to_string();
+
+    match bearer_token(&request) {
+        None => unauthorized(
+            &path,
+            "Missing API key, send it as 'Authorization: Bearer <key>'",
+        ),
+        Some(token) if expected.is_empty() || !keys_match(&expected, &token) => {
+            unauthorized(&path, "Invalid API key")
+        }
+        Some(_) => next.run(request).await,
+    }
+}
+
+/// Only the origins listed in the settings may call the API from a browser.
+pub fn cors_layer(origins: &[String]) -> CorsLayer {
+    let origins: Vec<HeaderValue> = origins
+        .iter()
+        .filter_map(|origin| match HeaderValue::from_str(origin.trim()) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                eprintln!("Ignoring invalid CORS origin: {}", origin);
+                None
+            }
+        })
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+}
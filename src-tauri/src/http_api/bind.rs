@@ -0,0 +1,55 @@
+use crate::settings::AppSettings;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+#[cfg(unix)]
+use std::path::PathBuf;
+
+/// Where the HTTP API listens, resolved from the settings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ApiBind {
+    Tcp(SocketAddr),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl ApiBind {
+    pub fn from_settings(s: &AppSettings) -> Result<Self, String> {
+        if !s.api_socket_path.trim().is_empty() {
+            #[cfg(unix)]
+            return Ok(ApiBind::Unix(PathBuf::from(s.api_socket_path.trim())));
+            #[cfg(not(unix))]
+            return Err("Unix domain sockets are not supported on this platform".to_string());
+        }
+
+        let ip: IpAddr = s
+            .api_bind_address
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid API bind address '{}'", s.api_bind_address))?;
+
+        if !ip.is_loopback() {
+            if !s.api_allow_external_bind {
+                return Err(format!(
+                    "Refusing to expose the HTTP API on {}. Set api_allow_external_bind to true to allow other machines to reach it.",
+                    ip
+                ));
+            }
+            println!(
+                "WARNING: HTTP API is bound to {}, other machines on this network can reach it",
+                ip
+            );
+        }
+
+        Ok(ApiBind::Tcp(SocketAddr::new(ip, s.api_port)))
+    }
+}
+
+impl fmt::Display for ApiBind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiBind::Tcp(addr) => write!(f, "http://{}", addr),
+            #[cfg(unix)]
+            ApiBind::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
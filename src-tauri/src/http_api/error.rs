@@ -0,0 +1,201 @@
+use crate::settings::{FieldError, SettingsError};
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Longest audio the upload endpoints transcribe in one go.
+pub const MAX_AUDIO_SECS: u32 = 60 * 60;
+
+/// Stable, machine-readable error codes. Never rename a variant, clients branch on them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidRequest,
+    MissingField,
+    UnsupportedFormat,
+    AudioTooLong,
+    PayloadTooLarge,
+    Unauthorized,
+    NotFound,
+    Busy,
+    RecordingInProgress,
+    NotRecording,
+    ModelUnavailable,
+    TranscriptionFailed,
+    InternalError,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Human-readable description, may change between versions
+    pub error: String,
+    pub code: ErrorCode,
+    /// The refused values, when a setting could not be changed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    InvalidRequest(String),
+    /// A setting was refused, `fields` says which and why
+    InvalidSettings(String, Vec<FieldError>),
+    MissingField(&'static str),
+    UnsupportedFormat(String),
+    /// Longer than `MAX_AUDIO_SECS`
+    AudioTooLong(String),
+    /// Over the request body limit
+    PayloadTooLarge(String),
+    Unauthorized(String),
+    NotFound(String),
+    /// The transcription queue cannot take more work
+    Busy(String),
+    RecordingInProgress,
+    NotRecording,
+    ModelUnavailable(String),
+    TranscriptionFailed(String),
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::InvalidRequest(_) | ApiError::InvalidSettings(..) => {
+                ErrorCode::InvalidRequest
+            }
+            ApiError::MissingField(_) => ErrorCode::MissingField,
+            ApiError::UnsupportedFormat(_) => ErrorCode::UnsupportedFormat,
+            ApiError::AudioTooLong(_) => ErrorCode::AudioTooLong,
+            ApiError::PayloadTooLarge(_) => ErrorCode::PayloadTooLarge,
+            ApiError::Unauthorized(_) => ErrorCode::Unauthorized,
+            ApiError::NotFound(_) => ErrorCode::NotFound,
+            ApiError::Busy(_) => ErrorCode::Busy,
+            ApiError::RecordingInProgress => ErrorCode::RecordingInProgress,
+            ApiError::NotRecording => ErrorCode::NotRecording,
+            ApiError::ModelUnavailable(_) => ErrorCode::ModelUnavailable,
+            ApiError::TranscriptionFailed(_) => ErrorCode::TranscriptionFailed,
+            ApiError::Internal(_) => ErrorCode::InternalError,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::InvalidRequest(_)
+            | ApiError::InvalidSettings(..)
+            | ApiError::MissingField(_) => StatusCode::BAD_REQUEST,
+            ApiError::UnsupportedFormat(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::AudioTooLong(_) | ApiError::PayloadTooLarge(_) => {
+                StatusCode::PAYLOAD_TOO_LARGE
+            }
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Busy(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::RecordingInProgress | ApiError::NotRecording => StatusCode::CONFLICT,
+            ApiError::ModelUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::TranscriptionFailed(_) | ApiError::Internal(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::MissingField(field) => {
+                format!("No '{}' field in multipart request", field)
+            }
+            ApiError::RecordingInProgress => "A recording is already in progress".to_string(),
+            ApiError::NotRecording => "No recording in progress".to_string(),
+            ApiError::InvalidRequest(message)
+            | ApiError::InvalidSettings(message, _)
+            | ApiError::UnsupportedFormat(message)
+            | ApiError::AudioTooLong(message)
+            | ApiError::PayloadTooLarge(message)
+            | ApiError::Unauthorized(message)
+            | ApiError::NotFound(message)
+            | ApiError::Busy(message)
+            | ApiError::ModelUnavailable(message)
+            | ApiError::TranscriptionFailed(message)
+            | ApiError::Internal(message) => message.clone(),
+        }
+    }
+
+    /// Map a multipart read failure, oversized uploads get their own code.
+    pub fn from_multipart(e: axum::extract::multipart::MultipartError) -> Self {
+        if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::PayloadTooLarge(format!("Upload is too large: {}", e.body_text()))
+        } else {
+            ApiError::InvalidRequest(format!("Failed to parse multipart: {}", e.body_text()))
+        }
+    }
+}
+
+impl From<SettingsError> for ApiError {
+    fn from(error: SettingsError) -> Self {
+        if error.not_found {
+            let message = match error.fields.first() {
+                Some(field) => field.message.clone(),
+                None => error.message,
+            };
+            ApiError::NotFound(message)
+        } else if !error.fields.is_empty() {
+            ApiError::InvalidSettings(error.message, error.fields)
+        } else {
+            ApiError::Internal(error.message)
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(ErrorResponse {
+            error: self.message(),
+            code: self.code(),
+            fields: match &self {
+                ApiError::InvalidSettings(_, fields) => fields.clone(),
+                _ => Vec::new(),
+            },
+        });
+        match self {
+            ApiError::Unauthorized(_) => {
+                (self.status(), [(header::WWW_AUTHENTICATE, "Bearer")], body).into_response()
+            }
+            _ => (self.status(), body).into_response(),
+        }
+    }
+}
+
+/// Reject uploads that are not RIFF/WAVE files before they reach the engine.
+pub fn ensure_wav(bytes: &[u8]) -> Result<(), ApiError> {
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE" {
+        Ok(())
+    } else {
+        Err(ApiError::UnsupportedFormat(
+            "Only WAV files are supported".to_string(),
+        ))
+    }
+}
+
+/// Reject audio longer than `MAX_AUDIO_SECS`, read from the WAV header.
+pub fn ensure_duration(bytes: &[u8]) -> Result<(), ApiError> {
+    let reader = hound::WavReader::new(std::io::Cursor::new(bytes))
+        .map_err(|e| ApiError::UnsupportedFormat(format!("Invalid WAV file: {}", e)))?;
+    let secs = reader.duration() / reader.spec().sample_rate.max(1);
+    if secs > MAX_AUDIO_SECS {
+        return Err(ApiError::AudioTooLong(format!(
+            "Audio is {} minutes long, at most {} minutes are supported",
+            secs / 60,
+            MAX_AUDIO_SECS / 60
+        )));
+    }
+    Ok(())
+}
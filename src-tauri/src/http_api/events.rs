@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::stream::Stream;
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tauri::{AppHandle, Listener, Manager};
+use tokio::sync::broadcast;
+use utoipa::IntoParams;
+
+/// App events re-published on the `/api/events` feed.
+pub const FORWARDED_EVENTS: [&str; 5] = [
+    "shortcut:start",
+    "shortcut:stop",
+    "history-updated",
+    "mic-level",
+    "transcription-complete",
+];
+
+const EVENT_BUS_CAPACITY: usize = 256;
+
+#[derive(Clone, Debug)]
+pub struct ApiEvent {
+    pub name: String,
+    /// JSON-encoded payload, as emitted by the app
+    pub payload: String,
+}
+
+#[derive(Clone)]
+pub struct ApiEventBus {
+    sender: broadcast::Sender<ApiEvent>,
+}
+
+impl ApiEventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ApiEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn publish(&self, event: ApiEvent) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for ApiEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forward the app events listed in `FORWARDED_EVENTS` to the API event bus.
+pub fn forward_app_events(app: &AppHandle, bus: &ApiEventBus) {
+    for name in FORWARDED_EVENTS {
+        let bus = bus.clone();
+        app.listen_any(name, move |event| {
+            bus.publish(ApiEvent {
+                name: name.to_string(),
+                payload: event.payload().to_string(),
+            });
+        });
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct EventsQuery {
+    /// Comma-separated list of event names to receive, all events when omitted
+    pub events: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "events",
+    params(EventsQuery),
+    responses(
+        (status = 200, description = "Server-sent events named after the app event, with its JSON payload as data", content_type = "text/event-stream", body = String),
+    )
+)]
+pub async fn events_handler(
+    State(app): State<Arc<AppHandle>>,
+    Query(query): Query<EventsQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let filter: Option<Vec<String>> = query.events.map(|events| {
+        events
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect()
+    });
+    let receiver = app.state::<ApiEventBus>().subscribe();
+
+    let stream = futures_util::stream::unfold(
+        (receiver, filter),
+        |(mut receiver, filter)| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if let Some(filter) = &filter {
+                            if !filter.contains(&event.name) {
+                                continue;
+                            }
+                        }
+                        let sse = Event::default().event(event.name).data(event.payload);
+                        return Some((Ok(sse), (receiver, filter)));
+                    }
+                    // A slow client missed some events, keep streaming the newer ones
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
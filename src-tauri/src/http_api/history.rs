@@ -0,0 +1,215 @@
+use super::error::{ApiError, ErrorResponse};
+use crate::history::{
+    self, ExportFormat, HistoryEntry, HistoryFilter, HistoryImport, ImportSummary,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize, IntoParams)]
+pub struct HistoryQuery {
+    /// Number of entries to skip, defaults to 0
+    pub offset: Option<usize>,
+    /// Page size, defaults to 20, at most 100
+    pub limit: Option<usize>,
+    /// Full-text search, every word must appear in the entry
+    pub q: Option<String>,
+    /// Only entries at or after this time, in seconds since the Unix epoch
+    pub from: Option<i64>,
+    /// Only entries at or before this time, in seconds since the Unix epoch
+    pub to: Option<i64>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// `json` (default), `csv` or `markdown`
+    pub format: Option<ExportFormat>,
+    /// Full-text search, every word must appear in the entry
+    pub q: Option<String>,
+    /// Only entries at or after this time, in seconds since the Unix epoch
+    pub from: Option<i64>,
+    /// Only entries at or before this time, in seconds since the Unix epoch
+    pub to: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+fn not_found(id: u64) -> ApiError {
+    ApiError::NotFound(format!("No history entry with id {}", id))
+}
+
+fn history_error(e: anyhow::Error) -> ApiError {
+    ApiError::Internal(format!("Failed to read history: {:#}", e))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/history",
+    tag = "history",
+    params(HistoryQuery),
+    responses(
+        (status = 200, description = "Most recent entries first", body = HistoryPage),
+        (status = 500, description = "`internal_error`", body = ErrorResponse),
+    )
+)]
+pub async fn list_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<HistoryPage>, ApiError> {
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let filter = HistoryFilter {
+        query: query.q,
+        from: query.from,
+        to: query.to,
+    };
+
+    let (entries, total) =
+        history::search_transcriptions(&app, &filter, offset, limit).map_err(history_error)?;
+    Ok(Json(HistoryPage {
+        entries,
+        total,
+        offset,
+        limit,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/history/latest",
+    tag = "history",
+    responses(
+        (status = 200, description = "Last dictation", body = HistoryEntry),
+        (status = 404, description = "`not_found`, the history is empty", body = ErrorResponse),
+    )
+)]
+pub async fn latest_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+) -> Result<Json<HistoryEntry>, ApiError> {
+    history::get_latest_transcription(&app)
+        .map_err(history_error)?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound("History is empty".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/history/{id}",
+    tag = "history",
+    params(("id" = u64, Path, description = "History entry id")),
+    responses(
+        (status = 200, description = "History entry", body = HistoryEntry),
+        (status = 404, description = "`not_found`", body = ErrorResponse),
+    )
+)]
+pub async fn get_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+    Path(id): Path<u64>,
+) -> Result<Json<HistoryEntry>, ApiError> {
+    history::get_transcription(&app, id)
+        .map_err(history_error)?
+        .map(Json)
+        .ok_or_else(|| not_found(id))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/history/{id}",
+    tag = "history",
+    params(("id" = u64, Path, description = "History entry id")),
+    responses(
+        (status = 204, description = "Entry deleted"),
+        (status = 404, description = "`not_found`", body = ErrorResponse),
+    )
+)]
+pub async fn delete_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+    Path(id): Path<u64>,
+) -> Result<StatusCode, ApiError> {
+    match history::delete_transcription(&app, id).map_err(history_error)? {
+        true => Ok(StatusCode::NO_CONTENT),
+        false => Err(not_found(id)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/history/export",
+    tag = "history",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Matching entries, oldest first, as a file download", content(
+            ("application/json"),
+            ("text/csv"),
+            ("text/markdown"),
+        )),
+        (status = 500, description = "`internal_error`", body = ErrorResponse),
+    )
+)]
+pub async fn export_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+    Query(query): Query<ExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let format = query.format.unwrap_or(ExportFormat::Json);
+    let filter = HistoryFilter {
+        query: query.q,
+        from: query.from,
+        to: query.to,
+    };
+
+    let content = history::export_transcriptions(&app, &filter, format).map_err(history_error)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"murmure-history.{}\"",
+                    format.extension()
+                ),
+            ),
+        ],
+        content,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/history/import",
+    tag = "history",
+    request_body(content = String, description = "A JSON export, or a list of entries", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Entries added under new ids, duplicates skipped", body = ImportSummary),
+        (status = 400, description = "`invalid_request`", body = ErrorResponse),
+        (status = 500, description = "`internal_error`", body = ErrorResponse),
+    )
+)]
+pub async fn import_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+    body: String,
+) -> Result<Json<ImportSummary>, ApiError> {
+    let data: HistoryImport = serde_json::from_str(&body)
+        .map_err(|e| ApiError::InvalidRequest(format!("Invalid history file: {}", e)))?;
+    history::import_transcriptions(&app, data)
+        .map(Json)
+        .map_err(|e| ApiError::Internal(format!("Failed to import history: {:#}", e)))
+}
@@ -0,0 +1,323 @@
+use super::error::{ApiError, ErrorResponse};
+use super::server::{read_audio_field, AudioUpload};
+use crate::audio;
+use axum::{
+    extract::{Multipart, Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use utoipa::ToSchema;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// The engine runs one inference at a time, more workers would only wait on its lock.
+const WORKER_THREADS: usize = 1;
+/// Finished jobs kept around so clients can still fetch their result.
+const MAX_FINISHED_JOBS: usize = 200;
+
+/// Stages reported through `Job::progress`. The engine does not report how far along a
+/// transcription is, so progress jumps from one stage to the next.
+const PROGRESS_LOADING_MODEL: f32 = 0.1;
+const PROGRESS_TRANSCRIBING: f32 = 0.3;
+const PROGRESS_CORRECTING: f32 = 0.9;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Serialize, Clone, Debug, ToSchema)]
+pub struct Job {
+    pub id: String,
+    pub status: JobStatus,
+    /// Coarse stage between 0 and 1: 0 queued, 0.1 loading the model, 0.3 transcribing,
+    /// 0.9 applying the dictionary, 1 finished
+    pub progress: f32,
+    /// Number of jobs that will run before this one, only set while queued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+    pub created_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip)]
+    sequence: u64,
+}
+
+struct PendingJob {
+    id: String,
+    audio_path: PathBuf,
+}
+
+#[derive(Default)]
+struct JobTable {
+    jobs: HashMap<String, Job>,
+    next_sequence: u64,
+}
+
+impl JobTable {
+    fn queued_count(&self) -> usize {
+        self.jobs
+            .values()
+            .filter(|job| job.status == JobStatus::Queued)
+            .count()
+    }
+
+    fn with_queue_position(&self, job: &Job) -> Job {
+        let mut job = job.clone();
+        if job.status == JobStatus::Queued {
+            let ahead = self
+                .jobs
+                .values()
+                .filter(|other| other.status == JobStatus::Queued && other.sequence < job.sequence)
+                .count();
+            job.queue_position = Some(ahead);
+        }
+        job
+    }
+
+    fn evict_finished(&mut self) {
+        let mut finished: Vec<(u64, String)> = self
+            .jobs
+            .values()
+            .filter(|job| matches!(job.status, JobStatus::Completed | JobStatus::Failed))
+            .map(|job| (job.sequence, job.id.clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+        finished.sort();
+        let excess = finished.len() - MAX_FINISHED_JOBS;
+        for (_, id) in finished.into_iter().take(excess) {
+            self.jobs.remove(&id);
+        }
+    }
+}
+
+pub enum SubmitError {
+    QueueFull(usize),
+    Io(String),
+}
+
+/// Transcription jobs submitted through `/api/jobs`, run on dedicated worker threads.
+#[derive(Clone)]
+pub struct JobQueue {
+    table: Arc<Mutex<JobTable>>,
+    sender: mpsc::Sender<PendingJob>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl JobQueue {
+    pub fn start(app: AppHandle) -> Self {
+        let (sender, receiver) = mpsc::channel::<PendingJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let table = Arc::new(Mutex::new(JobTable::default()));
+
+        for index in 0..WORKER_THREADS {
+            let app = app.clone();
+            let receiver = receiver.clone();
+            let table = table.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("murmure-job-{}", index))
+                .spawn(move || loop {
+                    let pending = match receiver.lock().recv() {
+                        Ok(pending) => pending,
+                        Err(_) => break,
+                    };
+                    run_job(&app, &table, pending);
+                });
+            if let Err(e) = spawned {
+                eprintln!("Failed to spawn transcription worker: {}", e);
+            }
+        }
+
+        Self { table, sender }
+    }
+
+    /// Blocks on file I/O, call it from a blocking task.
+    pub fn submit(&self, app: &AppHandle, audio: &[u8]) -> Result<Job, SubmitError> {
+        let max_queued = crate::settings::load_settings(app).api_max_queued_jobs;
+        // Checked again below, this only avoids writing the file for nothing
+        if self.queued_count() >= max_queued {
+            return Err(SubmitError::QueueFull(max_queued));
+        }
+
+        // Written before taking the lock, the other job calls don't wait on the disk
+        let id = uuid::Uuid::new_v4().to_string();
+        let audio_path = std::env::temp_dir().join(format!("murmure-job-{}.wav", id));
+        std::fs::write(&audio_path, audio)
+            .map_err(|e| SubmitError::Io(format!("Failed to write audio file: {}", e)))?;
+
+        let mut table = self.table.lock();
+        if table.queued_count() >= max_queued {
+            drop(table);
+            let _ = std::fs::remove_file(&audio_path);
+            return Err(SubmitError::QueueFull(max_queued));
+        }
+
+        let job = Job {
+            id: id.clone(),
+            status: JobStatus::Queued,
+            progress: 0.0,
+            queue_position: None,
+            created_at: now_secs(),
+            finished_at: None,
+            text: None,
+            error: None,
+            sequence: table.next_sequence,
+        };
+        table.next_sequence += 1;
+        table.jobs.insert(id.clone(), job.clone());
+
+        if self
+            .sender
+            .send(PendingJob {
+                id: id.clone(),
+                audio_path: audio_path.clone(),
+            })
+            .is_err()
+        {
+            table.jobs.remove(&id);
+            let _ = std::fs::remove_file(&audio_path);
+            return Err(SubmitError::Io(
+                "Transcription workers are not running".to_string(),
+            ));
+        }
+
+        Ok(table.with_queue_position(&job))
+    }
+
+    pub fn queued_count(&self) -> usize {
+        self.table.lock().queued_count()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Job> {
+        let table = self.table.lock();
+        table.jobs.get(id).map(|job| table.with_queue_position(job))
+    }
+}
+
+fn update_job(table: &Mutex<JobTable>, id: &str, update: impl FnOnce(&mut Job)) {
+    if let Some(job) = table.lock().jobs.get_mut(id) {
+        update(job);
+    }
+}
+
+fn run_job(app: &AppHandle, table: &Mutex<JobTable>, pending: PendingJob) {
+    update_job(table, &pending.id, |job| {
+        job.status = JobStatus::Running;
+        job.progress = PROGRESS_LOADING_MODEL;
+    });
+
+    let result = match audio::preload_engine(app) {
+        Ok(_) => {
+            update_job(table, &pending.id, |job| job.progress = PROGRESS_TRANSCRIBING);
+            match audio::transcribe_audio_file(&pending.audio_path) {
+                Ok(raw) => {
+                    update_job(table, &pending.id, |job| job.progress = PROGRESS_CORRECTING);
+                    Ok(audio::finish_upload(app, raw))
+                }
+                Err(e) => Err(format!("Transcription failed: {}", e)),
+            }
+        }
+        Err(e) => Err(format!("Model not available: {}", e)),
+    };
+
+    let _ = std::fs::remove_file(&pending.audio_path);
+
+    let mut guard = table.lock();
+    if let Some(job) = guard.jobs.get_mut(&pending.id) {
+        job.finished_at = Some(now_secs());
+        job.progress = 1.0;
+        match result {
+            Ok(text) => {
+                job.status = JobStatus::Completed;
+                job.text = Some(text);
+            }
+            Err(e) => {
+                eprintln!("Job {} failed: {}", pending.id, e);
+                job.status = JobStatus::Failed;
+                job.error = Some(e);
+            }
+        }
+    }
+    guard.evict_finished();
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/jobs",
+    tag = "jobs",
+    request_body(content = AudioUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 202, description = "Job queued, poll `/api/jobs/{id}` for the result", body = Job),
+        (status = 400, description = "`invalid_request` or `missing_field`", body = ErrorResponse),
+        (status = 413, description = "`audio_too_long` or `payload_too_large`", body = ErrorResponse),
+        (status = 415, description = "`unsupported_format`", body = ErrorResponse),
+        (status = 429, description = "`busy`, the queue is full", body = ErrorResponse),
+    )
+)]
+pub async fn submit_handler(
+    State(app): State<Arc<AppHandle>>,
+    mut multipart: Multipart,
+) -> Result<Response, ApiError> {
+    let audio = read_audio_field(&mut multipart).await?;
+
+    let queue = app.state::<JobQueue>().inner().clone();
+    let job = tokio::task::spawn_blocking(move || queue.submit(&app, &audio))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Job submission failed: {}", e)))?
+        .map_err(|e| match e {
+            SubmitError::QueueFull(max_queued) => ApiError::Busy(format!(
+                "Job queue is full ({} jobs waiting), retry later",
+                max_queued
+            )),
+            SubmitError::Io(e) => ApiError::Internal(e),
+        })?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/api/jobs/{}", job.id))],
+        Json(job),
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/jobs/{id}",
+    tag = "jobs",
+    params(("id" = String, Path, description = "Job id returned by `POST /api/jobs`")),
+    responses(
+        (status = 200, description = "Job status, progress and result", body = Job),
+        (status = 404, description = "`not_found`", body = ErrorResponse),
+    )
+)]
+pub async fn get_handler(
+    State(app): State<Arc<AppHandle>>,
+    Path(id): Path<String>,
+) -> Result<Json<Job>, ApiError> {
+    app.state::<JobQueue>()
+        .get(&id)
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("No job with id {}", id)))
+}
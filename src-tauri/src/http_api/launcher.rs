@@ -1,60 +1,81 @@
 use tauri::AppHandle;
 use tauri_plugin_dialog::DialogExt;
 
-use super::state::HttpApiState;
+use std::thread::JoinHandle;
+use tokio::sync::oneshot;
 
-pub fn spawn_http_api_thread(app_handle: AppHandle, port: u16, state: HttpApiState) {
+use super::bind::ApiBind;
+use super::state::ApiStatusCell;
+
+pub fn spawn_http_api_thread(
+    app_handle: AppHandle,
+    bind: ApiBind,
+    shutdown_rx: oneshot::Receiver<()>,
+    status: ApiStatusCell,
+) -> JoinHandle<()> {
     std::thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new();
         match rt {
             Ok(runtime) => {
                 if let Err(e) = runtime.block_on(super::server::start_http_api(
                     app_handle.clone(),
-                    port,
-                    state.clone(),
+                    bind.clone(),
+                    shutdown_rx,
+                    status.clone(),
                 )) {
                     let error_msg = e.to_string();
                     eprintln!("HTTP API error: {}", error_msg);
+                    status.set_error(error_msg.clone());
 
-                    let is_port_conflict = error_msg.to_lowercase().contains("address already in use")
+                    let is_address_in_use = error_msg.to_lowercase().contains("address already in use")
                         || error_msg.contains("address in use")
                         || error_msg.contains("10048")
                         || error_msg.to_lowercase().contains("adresse de socket");
 
-                    if is_port_conflict {
+                    // Socket conflicts already come with a readable message
+                    let port_conflict = match &bind {
+                        ApiBind::Tcp(addr) if is_address_in_use => Some(addr.port()),
+                        _ => None,
+                    };
+
+                    if let Some(port) = port_conflict {
                         let msg = format!(
                             "Failed to start HTTP API on port {}.\n\nThe port is already in use by another application.\n\nPlease change the port in Settings → System → API Port to an available port (1024-65535).",
                             port
                         );
-                        let _ = app_handle
+                        app_handle
                             .dialog()
                             .message(&msg)
                             .title("HTTP API Error")
                             .kind(tauri_plugin_dialog::MessageDialogKind::Error)
-                            .blocking_show();
+                            // Don't block this thread, the supervisor waits for it to exit
+                            .show(|_| {});
                     } else {
                         let msg = format!("Failed to start HTTP API: {}", error_msg);
-                        let _ = app_handle
+                        app_handle
                             .dialog()
                             .message(&msg)
                             .title("HTTP API Error")
                             .kind(tauri_plugin_dialog::MessageDialogKind::Error)
-                            .blocking_show();
+                            .show(|_| {});
                     }
                 }
+                // A transcription still running on the blocking pool must not hold the port
+                runtime.shutdown_background();
             }
             Err(e) => {
                 eprintln!("Failed to create async runtime for HTTP API: {}", e);
                 let msg = format!("Failed to create async runtime for HTTP API: {}", e);
-                let _ = app_handle
+                status.set_error(msg.clone());
+                app_handle
                     .dialog()
                     .message(&msg)
                     .title("HTTP API Error")
                     .kind(tauri_plugin_dialog::MessageDialogKind::Error)
-                    .blocking_show();
+                    .show(|_| {});
             }
         }
-    });
+    })
 }
 
 
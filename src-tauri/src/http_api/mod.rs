@@ -1,8 +1,24 @@
+pub mod auth;
+pub mod bind;
+pub mod error;
+pub mod events;
+pub mod history;
+pub mod jobs;
+pub mod monitoring;
+pub mod openai;
+pub mod openapi;
+pub mod profiles;
+pub mod recording;
 pub mod server;
 pub mod state;
+pub mod stream;
+#[cfg(unix)]
+pub mod unix;
 pub mod launcher;
 
-pub use state::HttpApiState;
-pub use launcher::spawn_http_api_thread;
+pub use bind::ApiBind;
+pub use events::{forward_app_events, ApiEventBus};
+pub use jobs::JobQueue;
+pub use state::{ApiStatus, HttpApiState};
 
 
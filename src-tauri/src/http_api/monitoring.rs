@@ -0,0 +1,131 @@
+use super::jobs::JobQueue;
+use crate::audio::{self, EngineInfo};
+use crate::metrics;
+use crate::model::Model;
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct HealthResponse {
+    pub status: String,
+    pub version: String,
+    pub uptime_secs: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ModelStatus {
+    /// Resolved model directory, absent when the model cannot be found
+    pub path: Option<String>,
+    pub available: bool,
+    #[serde(flatten)]
+    pub engine: EngineInfo,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReadyResponse {
+    pub ready: bool,
+    pub model: ModelStatus,
+    pub queued_jobs: usize,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "monitoring",
+    security(()),
+    responses((status = 200, description = "The API is up", body = HealthResponse))
+)]
+pub async fn health_handler() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        uptime_secs: metrics::STARTED_AT.elapsed().as_secs(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ready",
+    tag = "monitoring",
+    responses(
+        (status = 200, description = "The model is available", body = ReadyResponse),
+        (status = 503, description = "The model cannot be found", body = ReadyResponse),
+    )
+)]
+pub async fn ready_handler(
+    axum::extract::State(app): axum::extract::State<Arc<AppHandle>>,
+) -> Response {
+    let path = app
+        .state::<Arc<Model>>()
+        .get_model_path()
+        .ok()
+        .map(|path| path.display().to_string());
+    let available = path.is_some();
+    let body = ReadyResponse {
+        ready: available,
+        model: ModelStatus {
+            path,
+            available,
+            engine: audio::engine_info(),
+        },
+        queued_jobs: app.state::<JobQueue>().queued_count(),
+    };
+
+    let status = if available {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(body)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "monitoring",
+    responses(
+        (status = 200, description = "Prometheus text exposition format", content_type = "text/plain", body = String),
+    )
+)]
+pub async fn metrics_handler(
+    axum::extract::State(app): axum::extract::State<Arc<AppHandle>>,
+) -> Response {
+    metrics::JOB_QUEUE_DEPTH.set(app.state::<JobQueue>().queued_count() as i64);
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(),
+    )
+        .into_response()
+}
+
+/// Count requests and their latency per route.
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let started_at = Instant::now();
+
+    let response = next.run(request).await;
+
+    metrics::HTTP_REQUEST_DURATION
+        .with_label_values(&[&method, &route])
+        .observe(started_at.elapsed().as_secs_f64());
+    metrics::HTTP_REQUESTS
+        .with_label_values(&[&method, &route, response.status().as_str()])
+        .inc();
+    response
+}
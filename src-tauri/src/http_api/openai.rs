@@ -0,0 +1,429 @@
+use super::error::MAX_AUDIO_SECS;
+use crate::audio;
+use crate::engine::engine::TimestampGranularity;
+use crate::engine::transcription_engine::TranscriptionSegment;
+use axum::{
+    extract::{Multipart, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+use utoipa::ToSchema;
+
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// Error body following the OpenAI API shape so existing clients can surface it.
+#[derive(Serialize, ToSchema)]
+pub struct OpenAiErrorResponse {
+    pub error: OpenAiError,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OpenAiError {
+    pub message: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub param: Option<String>,
+    pub code: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OpenAiTranscriptionResponse {
+    pub text: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OpenAiVerboseTranscriptionResponse {
+    pub task: String,
+    pub language: String,
+    pub duration: f32,
+    pub text: String,
+    pub segments: Vec<OpenAiSegment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<OpenAiWord>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OpenAiSegment {
+    pub id: usize,
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OpenAiWord {
+    pub word: String,
+    pub start: f32,
+    pub end: f32,
+}
+
+/// Multipart form accepted by `/v1/audio/transcriptions`
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct OpenAiTranscriptionForm {
+    /// WAV file to transcribe
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    /// Accepted for compatibility, the local model is always used
+    pub model: Option<String>,
+    /// Echoed back in `verbose_json`
+    pub language: Option<String>,
+    /// `json` (default), `text`, `srt`, `vtt` or `verbose_json`
+    pub response_format: Option<String>,
+    /// `segment` (default) and/or `word`
+    #[schema(rename = "timestamp_granularities[]")]
+    pub timestamp_granularities: Option<Vec<String>>,
+}
+
+#[derive(Clone, Copy)]
+enum ResponseFormat {
+    Json,
+    Text,
+    Srt,
+    Vtt,
+    VerboseJson,
+}
+
+impl ResponseFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "json" => Some(Self::Json),
+            "text" => Some(Self::Text),
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            "verbose_json" => Some(Self::VerboseJson),
+            _ => None,
+        }
+    }
+}
+
+struct TranscriptionRequest {
+    file: Option<Vec<u8>>,
+    language: Option<String>,
+    response_format: ResponseFormat,
+    word_timestamps: bool,
+}
+
+fn error_response(status: StatusCode, message: String, param: Option<&str>) -> Response {
+    let kind = if status.is_client_error() {
+        "invalid_request_error"
+    } else {
+        "server_error"
+    };
+    (
+        status,
+        Json(OpenAiErrorResponse {
+            error: OpenAiError {
+                message,
+                kind: kind.to_string(),
+                param: param.map(|p| p.to_string()),
+                code: None,
+            },
+        }),
+    )
+        .into_response()
+}
+
+async fn read_request(mut multipart: Multipart) -> Result<TranscriptionRequest, Response> {
+    let mut request = TranscriptionRequest {
+        file: None,
+        language: None,
+        response_format: ResponseFormat::Json,
+        word_timestamps: false,
+    };
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                return Err(error_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed to parse multipart: {}", e),
+                    None,
+                ))
+            }
+        };
+
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "file" {
+            match field.bytes().await {
+                Ok(bytes) => request.file = Some(bytes.to_vec()),
+                Err(e) => {
+                    return Err(error_response(
+                        StatusCode::BAD_REQUEST,
+                        format!("Failed to read audio file: {}", e),
+                        Some("file"),
+                    ))
+                }
+            }
+            continue;
+        }
+
+        let value = match field.text().await {
+            Ok(value) => value,
+            Err(e) => {
+                return Err(error_response(
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed to read field '{}': {}", name, e),
+                    Some(&name),
+                ))
+            }
+        };
+
+        match name.as_str() {
+            "language" if !value.trim().is_empty() => {
+                request.language = Some(value.trim().to_string())
+            }
+            "response_format" => match ResponseFormat::parse(&value) {
+                Some(format) => request.response_format = format,
+                None => {
+                    return Err(error_response(
+                        StatusCode::BAD_REQUEST,
+                        format!(
+                            "Unsupported response_format '{}'. Expected one of: json, text, srt, verbose_json, vtt",
+                            value
+                        ),
+                        Some("response_format"),
+                    ))
+                }
+            },
+            "timestamp_granularities[]" | "timestamp_granularities" => match value.trim() {
+                "word" => request.word_timestamps = true,
+                "segment" => {}
+                other => {
+                    return Err(error_response(
+                        StatusCode::BAD_REQUEST,
+                        format!(
+                            "Unsupported timestamp granularity '{}'. Expected 'word' or 'segment'",
+                            other
+                        ),
+                        Some("timestamp_granularities[]"),
+                    ))
+                }
+            },
+            // Parakeet has a single model and no prompt/temperature controls, so `model`,
+            // `prompt` and `temperature` are accepted for compatibility and ignored.
+            _ => {}
+        }
+    }
+
+    Ok(request)
+}
+
+fn format_timestamp(seconds: f32, decimal_separator: char) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms % 3_600_000) / 60_000;
+    let secs = (total_ms % 60_000) / 1000;
+    let ms = total_ms % 1000;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        hours, minutes, secs, decimal_separator, ms
+    )
+}
+
+fn to_srt(segments: &[TranscriptionSegment]) -> String {
+    let mut out = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            format_timestamp(segment.start, ','),
+            format_timestamp(segment.end, ','),
+            segment.text.trim()
+        ));
+    }
+    out
+}
+
+fn to_vtt(segments: &[TranscriptionSegment]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for segment in segments {
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(segment.start, '.'),
+            format_timestamp(segment.end, '.'),
+            segment.text.trim()
+        ));
+    }
+    out
+}
+
+/// OpenAI-compatible `POST /v1/audio/transcriptions`, backed by the local Parakeet engine.
+#[utoipa::path(
+    post,
+    path = "/v1/audio/transcriptions",
+    tag = "openai",
+    request_body(content = OpenAiTranscriptionForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Transcription in the requested `response_format`, `verbose_json` answers an `OpenAiVerboseTranscriptionResponse`", content(
+            (OpenAiTranscriptionResponse = "application/json"),
+            (String = "text/plain"),
+            (String = "application/x-subrip"),
+            (String = "text/vtt"),
+        )),
+        (status = 400, description = "Invalid request, in the OpenAI error shape", body = OpenAiErrorResponse),
+        (status = 413, description = "Audio too long", body = OpenAiErrorResponse),
+        (status = 500, description = "Transcription failed", body = OpenAiErrorResponse),
+        (status = 503, description = "Model not available", body = OpenAiErrorResponse),
+    )
+)]
+pub async fn transcriptions_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+    multipart: Multipart,
+) -> Response {
+    let request = match read_request(multipart).await {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+
+    let Some(file) = request.file else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "No 'file' field in multipart request".to_string(),
+            Some("file"),
+        );
+    };
+
+    let temp_path = std::env::temp_dir().join(format!("murmure-{}.wav", uuid::Uuid::new_v4()));
+    if let Err(e) = std::fs::write(&temp_path, file) {
+        return error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to write audio file: {}", e),
+            None,
+        );
+    }
+    let samples = audio::read_wav_samples(&temp_path);
+    let _ = std::fs::remove_file(&temp_path);
+
+    let samples = match samples {
+        Ok(samples) => samples,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported audio file, expected a 16-bit PCM WAV: {}", e),
+                Some("file"),
+            )
+        }
+    };
+    let duration = samples.len() as f32 / SAMPLE_RATE;
+    if duration > MAX_AUDIO_SECS as f32 {
+        return error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "Audio is {} minutes long, at most {} minutes are supported",
+                duration as u32 / 60,
+                MAX_AUDIO_SECS / 60
+            ),
+            Some("file"),
+        );
+    }
+
+    // Inference is synchronous, keep it off the async workers
+    let engine_app = app.clone();
+    let results = tokio::task::spawn_blocking(move || {
+        audio::preload_engine(&engine_app).map_err(|e| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("Model not available: {}", e),
+            )
+        })?;
+        let granularities = [TimestampGranularity::Segment, TimestampGranularity::Word];
+        let started_at = Instant::now();
+        let mut results = audio::transcribe_samples_with_timestamps(samples, &granularities)
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Transcription failed: {}", e),
+                )
+            })?
+            .into_iter();
+        let inference = started_at.elapsed();
+        let (raw_text, mut segments) = match results.next() {
+            Some(result) => (result.text, result.segments),
+            None => (String::new(), Vec::new()),
+        };
+        let words = results.next().map(|r| r.segments).unwrap_or_default();
+        for segment in segments.iter_mut() {
+            segment.text =
+                audio::correct_transcription(&engine_app, std::mem::take(&mut segment.text));
+        }
+        let raw = audio::Transcription {
+            text: raw_text,
+            audio_secs: duration as f64,
+            inference,
+        };
+        let text = audio::finish_upload(&engine_app, raw);
+        Ok((text, segments, words))
+    })
+    .await
+    .unwrap_or_else(|e| {
+        Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Transcription task failed: {}", e),
+        ))
+    });
+    let (text, segments, words) = match results {
+        Ok(results) => results,
+        Err((status, message)) => return error_response(status, message, None),
+    };
+
+    match request.response_format {
+        ResponseFormat::Json => {
+            (StatusCode::OK, Json(OpenAiTranscriptionResponse { text })).into_response()
+        }
+        ResponseFormat::Text => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            text,
+        )
+            .into_response(),
+        ResponseFormat::Srt => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/x-subrip; charset=utf-8")],
+            to_srt(&segments),
+        )
+            .into_response(),
+        ResponseFormat::Vtt => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/vtt; charset=utf-8")],
+            to_vtt(&segments),
+        )
+            .into_response(),
+        ResponseFormat::VerboseJson => {
+            let response = OpenAiVerboseTranscriptionResponse {
+                task: "transcribe".to_string(),
+                // Parakeet detects the language internally without reporting it
+                language: request.language.unwrap_or_else(|| "unknown".to_string()),
+                duration,
+                text,
+                segments: segments
+                    .into_iter()
+                    .enumerate()
+                    .map(|(id, segment)| OpenAiSegment {
+                        id,
+                        start: segment.start,
+                        end: segment.end,
+                        text: segment.text,
+                    })
+                    .collect(),
+                words: request.word_timestamps.then(|| {
+                    words
+                        .into_iter()
+                        .map(|word| OpenAiWord {
+                            word: word.text,
+                            start: word.start,
+                            end: word.end,
+                        })
+                        .collect()
+                }),
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use super::error::{ErrorCode, ErrorResponse};
+use super::history::HistoryPage;
+use super::jobs::{Job, JobStatus};
+use super::server::{AudioUpload, TranscriptionResponse};
+use super::monitoring::{HealthResponse, ModelStatus, ReadyResponse};
+use super::openai::{
+    OpenAiError, OpenAiErrorResponse, OpenAiSegment, OpenAiTranscriptionForm,
+    OpenAiTranscriptionResponse, OpenAiVerboseTranscriptionResponse, OpenAiWord,
+};
+use super::stream::{AudioEncoding, ClientMessage, ServerMessage, StreamSegment};
+use super::profiles::SwitchProfileRequest;
+use crate::audio::{EngineInfo, RecordingStatus};
+use crate::dictionary::{DictionaryEntry, MatchStrictness};
+use crate::settings::FieldError;
+use crate::profiles::{OutputMethod, PostProcessingRule, Profile, ProfileList};
+use crate::history::{
+    ExportFormat, HistoryEntry, HistorySource, ImportSummary, TranscriptionMetadata,
+};
+use axum::Json;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Murmure Local API",
+        description = "Local speech-to-text API. Errors carry a stable `code`, see `ErrorCode`."
+    ),
+    paths(
+        super::server::transcribe_handler,
+        super::history::list_handler,
+        super::history::latest_handler,
+        super::history::get_handler,
+        super::history::delete_handler,
+        super::history::export_handler,
+        super::history::import_handler,
+        super::recording::start_handler,
+        super::recording::stop_handler,
+        super::recording::cancel_handler,
+        super::recording::status_handler,
+        super::profiles::list_handler,
+        super::profiles::switch_handler,
+        super::jobs::submit_handler,
+        super::jobs::get_handler,
+        super::monitoring::health_handler,
+        super::monitoring::ready_handler,
+        super::monitoring::metrics_handler,
+        super::openai::transcriptions_handler,
+        super::stream::stream_handler,
+        super::events::events_handler,
+        openapi_handler,
+    ),
+    components(schemas(
+        AudioUpload,
+        TranscriptionResponse,
+        ErrorResponse,
+        ErrorCode,
+        FieldError,
+        HistoryEntry,
+        TranscriptionMetadata,
+        HistorySource,
+        HistoryPage,
+        ExportFormat,
+        ImportSummary,
+        RecordingStatus,
+        Profile,
+        ProfileList,
+        DictionaryEntry,
+        MatchStrictness,
+        OutputMethod,
+        PostProcessingRule,
+        SwitchProfileRequest,
+        Job,
+        JobStatus,
+        HealthResponse,
+        ReadyResponse,
+        ModelStatus,
+        EngineInfo,
+        OpenAiTranscriptionForm,
+        OpenAiTranscriptionResponse,
+        OpenAiVerboseTranscriptionResponse,
+        OpenAiSegment,
+        OpenAiWord,
+        OpenAiErrorResponse,
+        OpenAiError,
+        AudioEncoding,
+        StreamSegment,
+        ClientMessage,
+        ServerMessage,
+    )),
+    modifiers(&BearerAuth),
+    security(("api_key" = []))
+)]
+pub struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/openapi.json",
+    tag = "monitoring",
+    security(()),
+    responses((status = 200, description = "This document", body = Object))
+)]
+pub async fn openapi_handler() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
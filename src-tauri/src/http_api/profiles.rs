@@ -0,0 +1,45 @@
+use super::error::{ApiError, ErrorResponse};
+use crate::profiles::{self, ProfileList};
+use crate::settings;
+use axum::{extract::State, Json};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct SwitchProfileRequest {
+    /// Name of the profile to make active
+    pub name: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/profiles",
+    tag = "profiles",
+    responses((status = 200, description = "All profiles and the active one", body = ProfileList))
+)]
+pub async fn list_handler(State(app): State<Arc<tauri::AppHandle>>) -> Json<ProfileList> {
+    Json(ProfileList::from(settings::load_settings(&app)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/profiles/active",
+    tag = "profiles",
+    request_body = SwitchProfileRequest,
+    responses(
+        (status = 200, description = "Profile switched", body = ProfileList),
+        (status = 400, description = "`invalid_request`, the profile cannot be activated, see `fields`", body = ErrorResponse),
+        (status = 404, description = "`not_found`, no profile with that name", body = ErrorResponse),
+    )
+)]
+pub async fn switch_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+    Json(request): Json<SwitchProfileRequest>,
+) -> Result<Json<ProfileList>, ApiError> {
+    let handle = app.as_ref().clone();
+    let s = tokio::task::spawn_blocking(move || profiles::switch_profile(&handle, &request.name))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Profile switch task failed: {}", e)))??;
+    Ok(Json(ProfileList::from(s)))
+}
@@ -0,0 +1,92 @@
+use super::error::{ApiError, ErrorResponse};
+use super::server::TranscriptionResponse;
+use crate::audio;
+use crate::history::HistorySource;
+use axum::{extract::State, http::StatusCode, Json};
+use std::sync::Arc;
+
+#[utoipa::path(
+    post,
+    path = "/api/recording/start",
+    tag = "recording",
+    responses(
+        (status = 200, description = "Recording started", body = audio::RecordingStatus),
+        (status = 409, description = "`recording_in_progress`", body = ErrorResponse),
+        (status = 500, description = "`internal_error`, no usable microphone", body = ErrorResponse),
+    )
+)]
+pub async fn start_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+) -> Result<Json<audio::RecordingStatus>, ApiError> {
+    let handle = app.as_ref().clone();
+    tokio::task::spawn_blocking(move || audio::record_audio(&handle))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Recording start task failed: {}", e)))?
+        .map_err(|e| match e {
+            audio::RecordError::AlreadyRecording => ApiError::RecordingInProgress,
+            audio::RecordError::Failed(message) => ApiError::Internal(message),
+        })?;
+
+    Ok(Json(audio::recording_status()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/recording/stop",
+    tag = "recording",
+    responses(
+        (status = 200, description = "Final text, empty when nothing could be transcribed", body = TranscriptionResponse),
+        (status = 409, description = "`not_recording`", body = ErrorResponse),
+    )
+)]
+pub async fn stop_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+) -> Result<Json<TranscriptionResponse>, ApiError> {
+    if !audio::is_recording() {
+        return Err(ApiError::NotRecording);
+    }
+
+    let handle = app.as_ref().clone();
+    let text =
+        tokio::task::spawn_blocking(move || audio::stop_recording(&handle, HistorySource::HttpApi))
+            .await
+            .map_err(|e| ApiError::Internal(format!("Recording stop task failed: {}", e)))?;
+
+    Ok(Json(TranscriptionResponse {
+        text: text.unwrap_or_default(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/recording/cancel",
+    tag = "recording",
+    responses(
+        (status = 204, description = "Recording discarded"),
+        (status = 409, description = "`not_recording`", body = ErrorResponse),
+    )
+)]
+pub async fn cancel_handler(
+    State(app): State<Arc<tauri::AppHandle>>,
+) -> Result<StatusCode, ApiError> {
+    if !audio::is_recording() {
+        return Err(ApiError::NotRecording);
+    }
+
+    let handle = app.as_ref().clone();
+    tokio::task::spawn_blocking(move || audio::cancel_recording(&handle))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Recording cancel task failed: {}", e)))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/recording/status",
+    tag = "recording",
+    responses((status = 200, description = "Current state of the recorder", body = audio::RecordingStatus))
+)]
+pub async fn status_handler() -> Json<audio::RecordingStatus> {
+    Json(audio::recording_status())
+}
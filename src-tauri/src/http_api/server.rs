@@ -1,152 +1,176 @@
 use crate::audio;
-use crate::dictionary::{fix_transcription_with_dictionary, get_cc_rules_path, Dictionary};
 use anyhow::Result;
 use axum::{
+    body::Bytes,
     extract::{DefaultBodyLimit, Multipart},
-    http::StatusCode,
-    response::IntoResponse,
-    routing::post,
+    routing::{get, post},
     Json, Router,
 };
+use super::bind::ApiBind;
+use super::error::{ensure_duration, ensure_wav, ApiError, ErrorResponse};
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
 use std::sync::Arc;
-use tauri::Manager;
+use std::time::Duration;
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct TranscriptionResponse {
     pub text: String,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct ErrorResponse {
-    pub error: String,
+/// Multipart form accepted by the upload endpoints
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct AudioUpload {
+    /// WAV file to transcribe
+    #[schema(value_type = String, format = Binary)]
+    pub audio: Vec<u8>,
 }
 
+/// How long in-flight requests may take to finish once the server is asked to stop.
+/// Event streams and WebSockets never finish on their own and are closed after it.
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub async fn start_http_api(
     app: tauri::AppHandle,
-    port: u16,
-    api_state: super::state::HttpApiState,
+    bind: ApiBind,
+    shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+    status: super::state::ApiStatusCell,
 ) -> Result<()> {
     let app = Arc::new(app);
+    crate::settings::ensure_api_key(&app).map_err(anyhow::Error::msg)?;
+    let cors_origins = crate::settings::load_settings(&app).api_cors_origins;
 
     let router = Router::new()
         .route("/api/transcribe", post(transcribe_handler))
+        .route(
+            "/v1/audio/transcriptions",
+            post(super::openai::transcriptions_handler),
+        )
+        .route("/api/stream", get(super::stream::stream_handler))
+        .route("/api/events", get(super::events::events_handler))
+        .route("/api/history", get(super::history::list_handler))
+        .route("/api/history/latest", get(super::history::latest_handler))
+        .route("/api/history/export", get(super::history::export_handler))
+        .route("/api/history/import", post(super::history::import_handler))
+        .route(
+            "/api/history/:id",
+            get(super::history::get_handler).delete(super::history::delete_handler),
+        )
+        .route("/api/recording/start", post(super::recording::start_handler))
+        .route("/api/recording/stop", post(super::recording::stop_handler))
+        .route("/api/recording/cancel", post(super::recording::cancel_handler))
+        .route("/api/recording/status", get(super::recording::status_handler))
+        .route("/api/profiles", get(super::profiles::list_handler))
+        .route("/api/profiles/active", post(super::profiles::switch_handler))
+        .route("/api/jobs", post(super::jobs::submit_handler))
+        .route("/api/jobs/:id", get(super::jobs::get_handler))
+        .route("/api/ready", get(super::monitoring::ready_handler))
+        .route("/metrics", get(super::monitoring::metrics_handler))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app.clone(),
+            super::auth::require_api_key,
+        ))
+        // Registered after the auth layer, so they can be fetched without a key
+        .route("/api/openapi.json", get(super::openapi::openapi_handler))
+        .route("/api/health", get(super::monitoring::health_handler))
+        .route_layer(axum::middleware::from_fn(super::monitoring::track_requests))
         .with_state(app.clone())
-        .layer(DefaultBodyLimit::max(100_000_000));
-
-    let addr = SocketAddr::from(([127, 0, 0, 1], port));
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-
-    println!("HTTP API listening on http://{}", addr);
-
-    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
-    api_state.set_shutdown_sender(shutdown_tx);
-
-    let server = axum::serve(listener, router);
-
-    tokio::select! {
-        _ = server => {
-            println!("HTTP API server ended normally");
+        .layer(DefaultBodyLimit::max(100_000_000))
+        .layer(super::auth::cors_layer(&cors_origins));
+
+    match &bind {
+        ApiBind::Tcp(addr) => {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            println!("HTTP API listening on {}", bind);
+            status.set_running();
+
+            // Fires once the shutdown signal arrives, to bound how long draining may take
+            let (draining_tx, draining_rx) = tokio::sync::oneshot::channel::<()>();
+            let server = axum::serve(listener, router).with_graceful_shutdown(async move {
+                let _ = shutdown_rx.await;
+                println!("HTTP API server shutdown signal received, draining connections");
+                let _ = draining_tx.send(());
+            });
+
+            tokio::select! {
+                result = server => result?,
+                _ = async {
+                    let _ = draining_rx.await;
+                    tokio::time::sleep(DRAIN_TIMEOUT).await;
+                } => {
+                    eprintln!("HTTP API connections still open after {:?}, closing them", DRAIN_TIMEOUT);
+                }
+            }
         }
-        _ = shutdown_rx => {
-            println!("HTTP API server shutdown signal received");
+        #[cfg(unix)]
+        ApiBind::Unix(path) => {
+            let listener = super::unix::bind(path)?;
+            println!("HTTP API listening on {}", bind);
+            status.set_running();
+
+            super::unix::serve(listener, path, router, shutdown_rx).await;
         }
     }
 
+    println!("HTTP API server stopped");
     Ok(())
 }
 
-async fn transcribe_handler(
-    axum::extract::State(app): axum::extract::State<Arc<tauri::AppHandle>>,
-    mut multipart: Multipart,
-) -> impl IntoResponse {
-    loop {
-        match multipart.next_field().await {
-            Ok(Some(field)) => {
-                if field.name() == Some("audio") {
-                    let bytes = match field.bytes().await {
-                        Ok(b) => b,
-                        Err(e) => {
-                            return (
-                                StatusCode::BAD_REQUEST,
-                                Json(ErrorResponse {
-                                    error: format!("Failed to read audio file: {}", e),
-                                }),
-                            )
-                                .into_response()
-                        }
-                    };
-
-                    let temp_path =
-                        std::env::temp_dir().join(format!("murmure-{}.wav", uuid::Uuid::new_v4()));
-
-                    if let Err(e) = std::fs::write(&temp_path, bytes) {
-                        return (
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            Json(ErrorResponse {
-                                error: format!("Failed to write audio file: {}", e),
-                            }),
-                        )
-                            .into_response();
-                    }
-
-                    let result = match audio::preload_engine(&app) {
-                        Ok(_) => match audio::transcribe_audio(&temp_path) {
-                            Ok(raw_text) => {
-                                let text = match get_cc_rules_path(&app) {
-                                    Ok(cc_rules_path) => {
-                                        let dictionary = app.state::<Dictionary>().get();
-                                        fix_transcription_with_dictionary(
-                                            raw_text,
-                                            dictionary,
-                                            cc_rules_path,
-                                        )
-                                    }
-                                    Err(_) => raw_text,
-                                };
-
-                                Ok(text)
-                            }
-                            Err(e) => Err(format!("Transcription failed: {}", e)),
-                        },
-                        Err(e) => Err(format!("Model not available: {}", e)),
-                    };
-
-                    let _ = std::fs::remove_file(&temp_path);
-
-                    return match result {
-                        Ok(text) => {
-                            (StatusCode::OK, Json(TranscriptionResponse { text })).into_response()
-                        }
-                        Err(e) => (
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            Json(ErrorResponse { error: e }),
-                        )
-                            .into_response(),
-                    };
-                }
-            }
-            Ok(None) => break,
-            Err(e) => {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse {
-                        error: format!("Failed to parse multipart: {}", e),
-                    }),
-                )
-                    .into_response()
-            }
+/// Read the `audio` field of a multipart upload and check that it is a WAV file.
+pub async fn read_audio_field(multipart: &mut Multipart) -> Result<Bytes, ApiError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(ApiError::from_multipart)?
+    {
+        if field.name() == Some("audio") {
+            let bytes = field.bytes().await.map_err(ApiError::from_multipart)?;
+            ensure_wav(&bytes)?;
+            ensure_duration(&bytes)?;
+            return Ok(bytes);
         }
     }
+    Err(ApiError::MissingField("audio"))
+}
 
-    (
-        StatusCode::BAD_REQUEST,
-        Json(ErrorResponse {
-            error: "No 'audio' field in multipart request".to_string(),
-        }),
+#[utoipa::path(
+    post,
+    path = "/api/transcribe",
+    tag = "transcription",
+    request_body(content = AudioUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Transcribed text", body = TranscriptionResponse),
+        (status = 400, description = "`invalid_request` or `missing_field`", body = ErrorResponse),
+        (status = 413, description = "`audio_too_long` or `payload_too_large`", body = ErrorResponse),
+        (status = 415, description = "`unsupported_format`", body = ErrorResponse),
+        (status = 500, description = "`transcription_failed` or `internal_error`", body = ErrorResponse),
+        (status = 503, description = "`model_unavailable`", body = ErrorResponse),
     )
-        .into_response()
+)]
+pub async fn transcribe_handler(
+    axum::extract::State(app): axum::extract::State<Arc<tauri::AppHandle>>,
+    mut multipart: Multipart,
+) -> Result<Json<TranscriptionResponse>, ApiError> {
+    let bytes = read_audio_field(&mut multipart).await?;
+
+    let temp_path = std::env::temp_dir().join(format!("murmure-{}.wav", uuid::Uuid::new_v4()));
+    std::fs::write(&temp_path, bytes)
+        .map_err(|e| ApiError::Internal(format!("Failed to write audio file: {}", e)))?;
+
+    // Inference is synchronous, keep it off the async workers
+    let audio_path = temp_path.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        audio::preload_engine(&app)
+            .map_err(|e| ApiError::ModelUnavailable(format!("Model not available: {}", e)))?;
+        let raw = audio::transcribe_audio_file(&audio_path)
+            .map_err(|e| ApiError::TranscriptionFailed(format!("Transcription failed: {}", e)))?;
+        Ok(audio::finish_upload(&app, raw))
+    })
+    .await
+    .unwrap_or_else(|e| Err(ApiError::Internal(format!("Transcription task failed: {}", e))));
+
+    let _ = std::fs::remove_file(&temp_path);
+
+    result.map(|text| Json(TranscriptionResponse { text }))
 }
-
-
@@ -1,35 +1,221 @@
-use std::sync::{Arc, Mutex};
+use super::bind::ApiBind;
+use super::launcher::spawn_http_api_thread;
+use crate::settings;
+use serde::Serialize;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use tauri::{AppHandle, Emitter};
 use tokio::sync::oneshot;
 
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiRunState {
+    Stopped,
+    Starting,
+    Running,
+    Error,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ApiStatus {
+    pub state: ApiRunState,
+    pub address: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// Shared status of the server, updated by the supervisor and the server thread.
+#[derive(Clone)]
+pub struct ApiStatusCell {
+    app: AppHandle,
+    status: Arc<Mutex<ApiStatus>>,
+}
+
+impl ApiStatusCell {
+    pub fn get(&self) -> ApiStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    fn update(&self, update: impl FnOnce(&mut ApiStatus)) {
+        let status = {
+            let mut guard = self.status.lock().unwrap();
+            update(&mut guard);
+            guard.clone()
+        };
+        let _ = self.app.emit("api-status-changed", status);
+    }
+
+    pub fn set_starting(&self, bind: &ApiBind) {
+        self.update(|status| {
+            status.state = ApiRunState::Starting;
+            status.address = Some(bind.to_string());
+        });
+    }
+
+    pub fn set_running(&self) {
+        self.update(|status| {
+            status.state = ApiRunState::Running;
+            status.last_error = None;
+        });
+    }
+
+    pub fn set_stopped(&self) {
+        self.update(|status| {
+            // `last_error` is kept so the UI can still explain why the server went down
+            status.state = ApiRunState::Stopped;
+            status.address = None;
+        });
+    }
+
+    pub fn set_error(&self, error: String) {
+        self.update(|status| {
+            status.state = ApiRunState::Error;
+            status.last_error = Some(error);
+        });
+    }
+}
+
+enum Command {
+    /// Start, restart or stop the server to match the current settings
+    Apply,
+    /// `Apply`, also starting a server the user stopped
+    Start,
+    /// Stop the server until it is started again or `api_enabled` is toggled
+    Stop,
+}
+
+/// Settings the server only reads when it starts, a change to them needs a restart.
+#[derive(PartialEq)]
+struct ServerConfig {
+    bind: ApiBind,
+    cors_origins: Vec<String>,
+}
+
+struct RunningServer {
+    config: ServerConfig,
+    shutdown_tx: oneshot::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl RunningServer {
+    /// Ask the server to drain its connections and wait until the port is released.
+    fn shutdown(self) {
+        let _ = self.shutdown_tx.send(());
+        if self.thread.join().is_err() {
+            eprintln!("HTTP API server thread panicked");
+        }
+    }
+}
+
+/// Supervises the HTTP API: at most one server runs at a time, and every start or stop goes
+/// through a single thread so changes are applied in order.
 #[derive(Clone)]
 pub struct HttpApiState {
-    shutdown_tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+    commands: mpsc::Sender<Command>,
+    status: ApiStatusCell,
 }
 
 impl HttpApiState {
-    pub fn new() -> Self {
-        Self {
-            shutdown_tx: Arc::new(Mutex::new(None)),
+    pub fn new(app: AppHandle) -> Self {
+        let status = ApiStatusCell {
+            app: app.clone(),
+            status: Arc::new(Mutex::new(ApiStatus {
+                state: ApiRunState::Stopped,
+                address: None,
+                last_error: None,
+            })),
+        };
+        let (commands, receiver) = mpsc::channel::<Command>();
+
+        let supervisor_status = status.clone();
+        let spawned = std::thread::Builder::new()
+            .name("murmure-api-supervisor".to_string())
+            .spawn(move || supervise(app, receiver, supervisor_status));
+        if let Err(e) = spawned {
+            eprintln!("Failed to spawn HTTP API supervisor: {}", e);
         }
+
+        Self { commands, status }
+    }
+
+    /// Reload the API settings and restart the server if needed.
+    pub fn apply(&self) {
+        let _ = self.commands.send(Command::Apply);
     }
 
-    pub fn set_shutdown_sender(&self, tx: oneshot::Sender<()>) {
-        let mut guard = self.shutdown_tx.lock().unwrap();
-        *guard = Some(tx);
+    pub fn start(&self) {
+        let _ = self.commands.send(Command::Start);
     }
 
     pub fn stop(&self) {
-        let mut guard = self.shutdown_tx.lock().unwrap();
-        if let Some(tx) = guard.take() {
-            let _ = tx.send(());
-        }
+        let _ = self.commands.send(Command::Stop);
     }
-}
 
-impl Default for HttpApiState {
-    fn default() -> Self {
-        Self::new()
+    pub fn status(&self) -> ApiStatus {
+        self.status.get()
     }
 }
 
+fn supervise(app: AppHandle, receiver: mpsc::Receiver<Command>, status: ApiStatusCell) {
+    let mut running: Option<RunningServer> = None;
+    // A manual stop outlasts settings changes, until the server is started or re-enabled
+    let mut stopped_by_user = false;
+    let mut api_enabled: Option<bool> = None;
+
+    for command in receiver {
+        let wanted = match command {
+            Command::Stop => {
+                stopped_by_user = true;
+                None
+            }
+            Command::Apply | Command::Start => {
+                let s = settings::load_settings(&app);
+                if matches!(command, Command::Start) || api_enabled != Some(s.api_enabled) {
+                    stopped_by_user = false;
+                }
+                api_enabled = Some(s.api_enabled);
+                (s.api_enabled && !stopped_by_user).then(|| {
+                    ApiBind::from_settings(&s).map(|bind| ServerConfig {
+                        bind,
+                        cors_origins: s.api_cors_origins.clone(),
+                    })
+                })
+            }
+        };
+
+        // Unrelated settings changes leave the server and its open streams alone. A server
+        // that failed to start is retried.
+        if let (Some(server), Some(Ok(config))) = (&running, &wanted) {
+            if server.config == *config && status.get().state != ApiRunState::Error {
+                continue;
+            }
+        }
 
+        if let Some(server) = running.take() {
+            server.shutdown();
+            status.set_stopped();
+        }
+
+        match wanted {
+            None => {}
+            Some(Ok(config)) => {
+                status.set_starting(&config.bind);
+                let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+                let thread = spawn_http_api_thread(
+                    app.clone(),
+                    config.bind.clone(),
+                    shutdown_rx,
+                    status.clone(),
+                );
+                running = Some(RunningServer {
+                    config,
+                    shutdown_tx,
+                    thread,
+                });
+            }
+            Some(Err(e)) => {
+                eprintln!("HTTP API not started: {}", e);
+                status.set_error(e);
+            }
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use crate::audio;
+use crate::dictionary::{fix_transcription_with_dictionary, get_cc_rules_path, Dictionary};
+use crate::engine::engine::TimestampGranularity;
+use crate::engine::transcription_engine::TranscriptionSegment;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::Manager;
+use utoipa::ToSchema;
+
+const SAMPLE_RATE: usize = 16000;
+/// Amount of new audio (in seconds) to accumulate before emitting a partial result
+const PARTIAL_INTERVAL_SECS: f32 = 1.0;
+/// Longest window (in seconds) kept open before it is finalized even without a sentence end
+const MAX_WINDOW_SECS: f32 = 20.0;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioEncoding {
+    PcmS16le,
+    PcmF32le,
+    Opus,
+}
+
+/// Text frame sent by the client, audio goes in binary frames
+#[derive(Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[schema(as = StreamClientMessage)]
+pub enum ClientMessage {
+    Config {
+        encoding: AudioEncoding,
+        #[serde(default = "default_sample_rate")]
+        sample_rate: usize,
+    },
+    Flush,
+    Stop,
+}
+
+fn default_sample_rate() -> usize {
+    SAMPLE_RATE
+}
+
+#[derive(Serialize, Clone, ToSchema)]
+pub struct StreamSegment {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+/// Text frame sent by the server
+#[derive(Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[schema(as = StreamServerMessage)]
+pub enum ServerMessage {
+    Ready {
+        encoding: AudioEncoding,
+        sample_rate: usize,
+    },
+    Partial {
+        text: String,
+        start: f32,
+        end: f32,
+        segments: Vec<StreamSegment>,
+    },
+    Final {
+        text: String,
+        start: f32,
+        end: f32,
+        segments: Vec<StreamSegment>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Audio received on one WebSocket connection that has not been finalized yet.
+struct StreamSession {
+    encoding: AudioEncoding,
+    #[cfg(feature = "opus")]
+    decoder: Option<audiopus::coder::Decoder>,
+    /// Trailing bytes of an incomplete PCM sample, kept for the next frame
+    pending_bytes: Vec<u8>,
+    /// 16 kHz mono samples since the last finalized segment
+    window: Vec<f32>,
+    /// Position of `window[0]` in the stream, in seconds
+    window_offset: f32,
+    samples_since_partial: usize,
+    audio_started: bool,
+}
+
+impl StreamSession {
+    fn new() -> Self {
+        Self {
+            encoding: AudioEncoding::PcmS16le,
+            #[cfg(feature = "opus")]
+            decoder: None,
+            pending_bytes: Vec::new(),
+            window: Vec::new(),
+            window_offset: 0.0,
+            samples_since_partial: 0,
+            audio_started: false,
+        }
+    }
+
+    fn configure(&mut self, encoding: AudioEncoding, sample_rate: usize) -> Result<(), String> {
+        if self.audio_started {
+            return Err("Configuration must be sent before any audio frame".to_string());
+        }
+        if sample_rate != SAMPLE_RATE {
+            return Err(format!(
+                "Unsupported sample rate {}, only {} Hz mono audio is accepted",
+                sample_rate, SAMPLE_RATE
+            ));
+        }
+        if encoding == AudioEncoding::Opus {
+            #[cfg(feature = "opus")]
+            {
+                let decoder = audiopus::coder::Decoder::new(
+                    audiopus::SampleRate::Hz16000,
+                    audiopus::Channels::Mono,
+                )
+                .map_err(|e| format!("Failed to create Opus decoder: {}", e))?;
+                self.decoder = Some(decoder);
+            }
+            #[cfg(not(feature = "opus"))]
+            return Err("This build of Murmure was compiled without Opus support".to_string());
+        }
+        self.encoding = encoding;
+        Ok(())
+    }
+
+    fn push_frame(&mut self, frame: &[u8]) -> Result<(), String> {
+        self.audio_started = true;
+        let samples = match self.encoding {
+            AudioEncoding::PcmS16le => self.decode_pcm(frame, 2, |b| {
+                i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32
+            }),
+            AudioEncoding::PcmF32le => {
+                self.decode_pcm(frame, 4, |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            }
+            AudioEncoding::Opus => self.decode_opus(frame)?,
+        };
+        self.samples_since_partial += samples.len();
+        self.window.extend(samples);
+        Ok(())
+    }
+
+    fn decode_pcm(
+        &mut self,
+        frame: &[u8],
+        sample_size: usize,
+        convert: impl Fn(&[u8]) -> f32,
+    ) -> Vec<f32> {
+        self.pending_bytes.extend_from_slice(frame);
+        let complete = self.pending_bytes.len() - self.pending_bytes.len() % sample_size;
+        let samples = self.pending_bytes[..complete]
+            .chunks_exact(sample_size)
+            .map(convert)
+            .collect();
+        self.pending_bytes.drain(..complete);
+        samples
+    }
+
+    #[cfg(feature = "opus")]
+    fn decode_opus(&mut self, frame: &[u8]) -> Result<Vec<f32>, String> {
+        use audiopus::{packet::Packet, MutSignals};
+
+        let decoder = self
+            .decoder
+            .as_mut()
+            .ok_or_else(|| "Opus decoder not initialized".to_string())?;
+        // 120 ms is the longest Opus frame
+        let mut output = vec![0.0f32; SAMPLE_RATE * 120 / 1000];
+        let packet = Packet::try_from(frame).map_err(|e| format!("Invalid Opus packet: {}", e))?;
+        let signals =
+            MutSignals::try_from(&mut output).map_err(|e| format!("Invalid Opus buffer: {}", e))?;
+        let decoded = decoder
+            .decode_float(Some(packet), signals, false)
+            .map_err(|e| format!("Failed to decode Opus packet: {}", e))?;
+        output.truncate(decoded);
+        Ok(output)
+    }
+
+    #[cfg(not(feature = "opus"))]
+    fn decode_opus(&mut self, _frame: &[u8]) -> Result<Vec<f32>, String> {
+        Err("This build of Murmure was compiled without Opus support".to_string())
+    }
+
+    fn window_secs(&self) -> f32 {
+        self.window.len() as f32 / SAMPLE_RATE as f32
+    }
+
+    /// Drop the first `secs` seconds of the window once they have been finalized.
+    fn advance(&mut self, secs: f32) {
+        let cut = ((secs.max(0.0) * SAMPLE_RATE as f32) as usize).min(self.window.len());
+        self.window.drain(..cut);
+        self.window_offset += cut as f32 / SAMPLE_RATE as f32;
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/stream",
+    tag = "transcription",
+    responses(
+        (status = 101, description = "WebSocket upgrade. Send audio in binary frames and `StreamClientMessage` text frames, receive `StreamServerMessage` text frames"),
+    )
+)]
+pub async fn stream_handler(
+    ws: WebSocketUpgrade,
+    State(app): State<Arc<tauri::AppHandle>>,
+) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, app))
+}
+
+async fn handle_socket(mut socket: WebSocket, app: Arc<tauri::AppHandle>) {
+    let mut session = StreamSession::new();
+
+    if send(
+        &mut socket,
+        &ServerMessage::Ready {
+            encoding: session.encoding,
+            sample_rate: SAMPLE_RATE,
+        },
+    )
+    .await
+    .is_err()
+    {
+        return;
+    }
+
+    while let Some(message) = socket.recv().await {
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                eprintln!("WebSocket stream error: {}", e);
+                return;
+            }
+        };
+
+        let result = match message {
+            Message::Binary(frame) => match session.push_frame(&frame) {
+                Ok(()) => {
+                    let interval = (PARTIAL_INTERVAL_SECS * SAMPLE_RATE as f32) as usize;
+                    if session.samples_since_partial >= interval {
+                        process_window(&mut socket, &app, &mut session, false).await
+                    } else {
+                        Ok(())
+                    }
+                }
+                Err(message) => send(&mut socket, &ServerMessage::Error { message }).await,
+            },
+            Message::Text(text) => match serde_json::from_str::<ClientMessage>(&text) {
+                Ok(ClientMessage::Config {
+                    encoding,
+                    sample_rate,
+                }) => match session.configure(encoding, sample_rate) {
+                    Ok(()) => {
+                        send(
+                            &mut socket,
+                            &ServerMessage::Ready {
+                                encoding,
+                                sample_rate,
+                            },
+                        )
+                        .await
+                    }
+                    Err(message) => send(&mut socket, &ServerMessage::Error { message }).await,
+                },
+                Ok(ClientMessage::Flush) => {
+                    process_window(&mut socket, &app, &mut session, true).await
+                }
+                Ok(ClientMessage::Stop) => {
+                    let _ = process_window(&mut socket, &app, &mut session, true).await;
+                    let _ = socket.send(Message::Close(None)).await;
+                    return;
+                }
+                Err(e) => {
+                    send(
+                        &mut socket,
+                        &ServerMessage::Error {
+                            message: format!("Invalid control message: {}", e),
+                        },
+                    )
+                    .await
+                }
+            },
+            Message::Close(_) => return,
+            Message::Ping(_) | Message::Pong(_) => Ok(()),
+        };
+
+        if result.is_err() {
+            return;
+        }
+    }
+}
+
+/// Transcribe the open window, finalize every completed sentence and report the rest as partial.
+///
+/// With `finalize_all` the whole window is finalized, which is used for `flush` and `stop`.
+async fn process_window(
+    socket: &mut WebSocket,
+    app: &Arc<tauri::AppHandle>,
+    session: &mut StreamSession,
+    finalize_all: bool,
+) -> Result<(), axum::Error> {
+    session.samples_since_partial = 0;
+    if session.window.is_empty() {
+        return Ok(());
+    }
+
+    let segments = match transcribe_window(app.clone(), session.window.clone()).await {
+        Ok(segments) => segments,
+        Err(e) => {
+            return send(
+                socket,
+                &ServerMessage::Error {
+                    message: format!("Transcription failed: {}", e),
+                },
+            )
+            .await
+        }
+    };
+
+    let finalize_count = if finalize_all || session.window_secs() >= MAX_WINDOW_SECS {
+        segments.len()
+    } else {
+        // The last segment may still be growing, everything before it ended on a sentence break
+        segments.len().saturating_sub(1)
+    };
+
+    let offset = session.window_offset;
+    let (finals, partials) = segments.split_at(finalize_count);
+
+    if !finals.is_empty() {
+        let cut = if finalize_count == segments.len() {
+            session.window_secs()
+        } else {
+            finals[finals.len() - 1].end
+        };
+        let raw_text = join_text(finals);
+        let text = match get_cc_rules_path(app) {
+            Ok(cc_rules_path) => {
+                let dictionary = app.state::<Dictionary>().get();
+                fix_transcription_with_dictionary(raw_text, dictionary, cc_rules_path)
+            }
+            Err(_) => raw_text,
+        };
+        send(
+            socket,
+            &ServerMessage::Final {
+                text,
+                start: offset + finals[0].start,
+                end: offset + cut,
+                segments: to_stream_segments(finals, offset),
+            },
+        )
+        .await?;
+        session.advance(cut);
+    } else if finalize_all || session.window_secs() >= MAX_WINDOW_SECS {
+        // Nothing intelligible was said, discard the audio rather than transcribe it again
+        let secs = session.window_secs();
+        session.advance(secs);
+    }
+
+    if !partials.is_empty() {
+        send(
+            socket,
+            &ServerMessage::Partial {
+                text: join_text(partials),
+                start: offset + partials[0].start,
+                end: offset + partials[partials.len() - 1].end,
+                segments: to_stream_segments(partials, offset),
+            },
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn transcribe_window(
+    app: Arc<tauri::AppHandle>,
+    samples: Vec<f32>,
+) -> anyhow::Result<Vec<TranscriptionSegment>> {
+    tokio::task::spawn_blocking(move || {
+        audio::preload_engine(&app)?;
+        let results =
+            audio::transcribe_samples_with_timestamps(samples, &[TimestampGranularity::Segment])?;
+        Ok(results
+            .into_iter()
+            .next()
+            .map(|result| result.segments)
+            .unwrap_or_default())
+    })
+    .await?
+}
+
+fn join_text(segments: &[TranscriptionSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn to_stream_segments(segments: &[TranscriptionSegment], offset: f32) -> Vec<StreamSegment> {
+    segments
+        .iter()
+        .map(|segment| StreamSegment {
+            start: offset + segment.start,
+            end: offset + segment.end,
+            text: segment.text.clone(),
+        })
+        .collect()
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
+    let payload = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::Text(payload)).await
+}
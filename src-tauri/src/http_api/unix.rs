@@ -0,0 +1,99 @@
+use super::server::DRAIN_TIMEOUT;
+use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::server::graceful::GracefulShutdown;
+use hyper_util::service::TowerToHyperService;
+use std::fs::DirBuilder;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::Path;
+use tokio::net::UnixListener;
+use tokio::sync::oneshot;
+
+/// Only the current user may connect to the socket.
+const SOCKET_PERMISSIONS: u32 = 0o600;
+/// The socket is created in a private directory, so it is never reachable with the umask's
+/// permissions before they are restricted.
+const STAGING_DIR_PERMISSIONS: u32 = 0o700;
+
+pub fn bind(path: &Path) -> anyhow::Result<UnixListener> {
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            anyhow::bail!("Socket {} is already in use", path.display());
+        }
+        // Left behind by a previous run that did not shut down cleanly
+        std::fs::remove_file(path)?;
+    }
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent)?;
+
+    let staging_dir = parent.join(format!(".murmure-socket-{}", uuid::Uuid::new_v4()));
+    DirBuilder::new()
+        .mode(STAGING_DIR_PERMISSIONS)
+        .create(&staging_dir)?;
+    let staged = staging_dir.join("api.sock");
+    let result = (|| -> anyhow::Result<UnixListener> {
+        let listener = UnixListener::bind(&staged)?;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(SOCKET_PERMISSIONS))?;
+        std::fs::rename(&staged, path)?;
+        Ok(listener)
+    })();
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&staging_dir);
+    result
+}
+
+pub async fn serve(
+    listener: UnixListener,
+    path: &Path,
+    router: Router,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let builder = Builder::new(TokioExecutor::new());
+    let graceful = GracefulShutdown::new();
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let stream = match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        eprintln!("Failed to accept HTTP API connection: {}", e);
+                        continue;
+                    }
+                };
+                let service = TowerToHyperService::new(router.clone());
+                let connection = builder
+                    .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                    .into_owned();
+                let connection = graceful.watch(connection);
+                tokio::spawn(async move {
+                    if let Err(e) = connection.await {
+                        eprintln!("HTTP API connection error: {}", e);
+                    }
+                });
+            }
+            _ = &mut shutdown => {
+                println!("HTTP API server shutdown signal received, draining connections");
+                break;
+            }
+        }
+    }
+
+    drop(listener);
+    if let Err(e) = std::fs::remove_file(path) {
+        eprintln!("Failed to remove socket {}: {}", path.display(), e);
+    }
+    if tokio::time::timeout(DRAIN_TIMEOUT, graceful.shutdown())
+        .await
+        .is_err()
+    {
+        eprintln!(
+            "HTTP API connections still open after {:?}, closing them",
+            DRAIN_TIMEOUT
+        );
+    }
+}
@@ -1,27 +1,35 @@
 mod audio;
+mod bundle;
 mod clipboard;
 mod commands;
 mod dictionary;
+mod encryption;
 mod engine;
 mod history;
 mod http_api;
+mod metrics;
 mod model;
 mod overlay;
+mod profiles;
 mod settings;
 mod shortcuts;
+mod storage;
 mod tray_icon;
+mod webhooks;
 
 use audio::preload_engine;
 use commands::*;
 use dictionary::Dictionary;
-use http_api::HttpApiState;
+use http_api::{ApiEventBus, HttpApiState};
 use model::Model;
 use shortcuts::init_shortcuts;
 use std::sync::Arc;
 use tauri::{DeviceEventFilter, Manager};
 use tray_icon::setup_tray;
 
-use crate::shortcuts::{LastTranscriptShortcutKeys, RecordShortcutKeys, TranscriptionSuspended};
+use crate::shortcuts::{
+    LastTranscriptShortcutKeys, ProfileShortcutKeys, RecordShortcutKeys, TranscriptionSuspended,
+};
 
 fn show_main_window(app: &tauri::AppHandle) {
     if let Some(main_window) = app.get_webview_window("main") {
@@ -53,9 +61,25 @@ pub fn run() {
                 Arc::new(Model::new(app.handle().clone()).expect("Failed to initialize model"));
             app.manage(model);
 
+            once_cell::sync::Lazy::force(&metrics::STARTED_AT);
+
+            // Before anything reads settings or history
+            encryption::init(&app.handle());
+
+            app.manage(settings::SettingsStore::load(&app.handle()));
+            bundle::apply_startup_bundle(&app.handle());
+            if let Err(e) = app.state::<settings::SettingsStore>().watch(&app.handle()) {
+                eprintln!("Failed to watch settings.json: {}", e);
+            }
             let s = settings::load_settings(&app.handle());
-            app.manage(Dictionary::new(s.dictionary.clone()));
-            app.manage(HttpApiState::new());
+            app.manage(Dictionary::new(s.active_profile().dictionary.clone()));
+            app.manage(HttpApiState::new(app.handle().clone()));
+            app.manage(webhooks::WebhookLog::new());
+
+            let api_events = ApiEventBus::new();
+            http_api::forward_app_events(app.handle(), &api_events);
+            app.manage(api_events);
+            app.manage(http_api::JobQueue::start(app.handle().clone()));
 
             match preload_engine(&app.handle()) {
                 Ok(_) => println!("Transcription engine ready"),
@@ -65,26 +89,28 @@ pub fn run() {
             setup_tray(&app.handle())?;
 
             overlay::create_recording_overlay(&app.handle());
-            if s.overlay_mode.as_str() == "always" {
+            if s.overlay_mode == settings::OverlayMode::Always {
                 if let Some(overlay_window) = app.get_webview_window("recording_overlay") {
                     let _ = overlay_window.show();
                 }
             }
 
-            let record_keys = shortcuts::parse_binding_keys(&s.record_shortcut);
+            let record_keys = shortcuts::parse_binding_keys(&s.active_profile().record_shortcut);
             app.manage(RecordShortcutKeys::new(record_keys));
 
-            let last_transcript_keys = shortcuts::parse_binding_keys(&s.last_transcript_shortcut);
+            let last_transcript_keys =
+                shortcuts::parse_binding_keys(&s.active_profile().last_transcript_shortcut);
             app.manage(LastTranscriptShortcutKeys::new(last_transcript_keys));
 
+            let profile_keys = shortcuts::parse_binding_keys(&s.profile_shortcut);
+            app.manage(ProfileShortcutKeys::new(profile_keys));
+
             app.manage(TranscriptionSuspended::new(false));
 
             init_shortcuts(app.handle().clone());
 
             if s.api_enabled {
-                let app_handle = app.handle().clone();
-                let state = app_handle.state::<HttpApiState>().inner().clone();
-                crate::http_api::spawn_http_api_thread(app_handle, s.api_port, state);
+                app.state::<HttpApiState>().apply();
             }
 
             Ok(())
@@ -99,6 +125,12 @@ pub fn run() {
             is_model_available,
             get_model_path,
             get_recent_transcriptions,
+            search_history,
+            export_history,
+            import_history,
+            retranscribe_history_entry,
+            get_recordings_retention,
+            set_recordings_retention,
             get_record_shortcut,
             set_record_shortcut,
             set_dictionary,
@@ -115,8 +147,40 @@ pub fn run() {
             set_api_enabled,
             get_api_port,
             set_api_port,
+            get_api_key,
+            regenerate_api_key,
+            get_api_cors_origins,
+            set_api_cors_origins,
+            get_api_bind_address,
+            set_api_bind_address,
+            get_api_allow_external_bind,
+            set_api_allow_external_bind,
+            get_api_socket_path,
+            set_api_socket_path,
+            get_webhooks,
+            set_webhooks,
+            get_webhook_deliveries,
             start_http_api_server,
             stop_http_api_server,
+            get_api_status,
+            get_encryption_status,
+            enable_encryption,
+            disable_encryption,
+            unlock_encryption,
+            rotate_encryption_key,
+            take_storage_recoveries,
+            get_profiles,
+            switch_profile,
+            create_profile,
+            rename_profile,
+            delete_profile,
+            set_output_method,
+            set_post_processing_rules,
+            get_profile_shortcut,
+            set_profile_shortcut,
+            export_config_bundle,
+            preview_config_bundle,
+            import_config_bundle,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
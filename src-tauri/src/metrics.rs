@@ -0,0 +1,123 @@
+use once_cell::sync::Lazy;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder,
+};
+use std::time::{Duration, Instant};
+
+pub static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+pub static STARTED_AT: Lazy<Instant> = Lazy::new(Instant::now);
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(metric: T) -> T {
+    if let Err(e) = REGISTRY.register(Box::new(metric.clone())) {
+        eprintln!("Failed to register metric: {}", e);
+    }
+    metric
+}
+
+pub static HTTP_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("murmure_http_requests_total", "HTTP API requests"),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+pub static HTTP_REQUEST_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "murmure_http_request_duration_seconds",
+                "HTTP API request latency",
+            )
+            .buckets(vec![
+                0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+            ]),
+            &["method", "route"],
+        )
+        .expect("valid metric"),
+    )
+});
+
+pub static TRANSCRIPTIONS: Lazy<IntCounter> = Lazy::new(|| {
+    register(
+        IntCounter::new(
+            "murmure_transcriptions_total",
+            "Transcriptions run by the engine",
+        )
+        .expect("valid metric"),
+    )
+});
+
+pub static TRANSCRIPTION_DURATION: Lazy<Histogram> = Lazy::new(|| {
+    register(
+        Histogram::with_opts(
+            HistogramOpts::new(
+                "murmure_transcription_duration_seconds",
+                "Time spent in the engine per transcription",
+            )
+            .buckets(vec![0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 120.0]),
+        )
+        .expect("valid metric"),
+    )
+});
+
+pub static REAL_TIME_FACTOR: Lazy<Histogram> = Lazy::new(|| {
+    register(
+        Histogram::with_opts(
+            HistogramOpts::new(
+                "murmure_transcription_real_time_factor",
+                "Processing time divided by audio duration, lower is faster",
+            )
+            .buckets(vec![0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1.0, 2.0]),
+        )
+        .expect("valid metric"),
+    )
+});
+
+pub static JOB_QUEUE_DEPTH: Lazy<IntGauge> = Lazy::new(|| {
+    register(
+        IntGauge::new("murmure_job_queue_depth", "Jobs waiting in /api/jobs")
+            .expect("valid metric"),
+    )
+});
+
+pub static ENGINE_LOADED: Lazy<IntGauge> = Lazy::new(|| {
+    register(
+        IntGauge::new(
+            "murmure_engine_loaded",
+            "1 when the model is loaded in memory",
+        )
+        .expect("valid metric"),
+    )
+});
+
+/// Record one engine run over `audio_secs` seconds of 16 kHz audio.
+pub fn observe_transcription(audio_secs: f64, elapsed: Duration) {
+    let elapsed = elapsed.as_secs_f64();
+    TRANSCRIPTIONS.inc();
+    TRANSCRIPTION_DURATION.observe(elapsed);
+    if audio_secs > 0.0 {
+        REAL_TIME_FACTOR.observe(elapsed / audio_secs);
+    }
+}
+
+/// Render every metric in the Prometheus text format.
+pub fn render() -> String {
+    // Metrics register on first use, force them so every series shows up from the first scrape
+    Lazy::force(&HTTP_REQUESTS);
+    Lazy::force(&HTTP_REQUEST_DURATION);
+    Lazy::force(&TRANSCRIPTIONS);
+    Lazy::force(&TRANSCRIPTION_DURATION);
+    Lazy::force(&REAL_TIME_FACTOR);
+    Lazy::force(&JOB_QUEUE_DEPTH);
+    Lazy::force(&ENGINE_LOADED);
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer) {
+        eprintln!("Failed to encode metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
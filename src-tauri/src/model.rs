@@ -13,6 +13,10 @@ impl Model {
         Ok(Self { app_handle })
     }
 
+    pub fn name(&self) -> &'static str {
+        MODEL_FILENAME
+    }
+
     pub fn get_model_path(&self) -> Result<PathBuf> {
         // Essayer plusieurs emplacements possibles pour le modèle
         let possible_paths = vec![
@@ -1,4 +1,4 @@
-use crate::settings;
+use crate::settings::{self, OverlayPosition};
 use tauri::{AppHandle, Emitter, Manager, WebviewWindowBuilder};
 
 const OVERLAY_WIDTH: f64 = 80.0;
@@ -21,9 +21,11 @@ fn calculate_overlay_position(app_handle: &AppHandle) -> Option<(f64, f64)> {
 
         let x = work_x + (work_w - OVERLAY_WIDTH) / 2.0;
         let s = settings::load_settings(app_handle);
-        let y = match s.overlay_position.as_str() {
-            "top" => work_y + work_h * OVERLAY_TOP_OFFSET_PCT,
-            _ => work_y + work_h * (1.0 - OVERLAY_BOTTOM_OFFSET_PCT) - OVERLAY_HEIGHT,
+        let y = match s.overlay_position {
+            OverlayPosition::Top => work_y + work_h * OVERLAY_TOP_OFFSET_PCT,
+            OverlayPosition::Bottom => {
+                work_y + work_h * (1.0 - OVERLAY_BOTTOM_OFFSET_PCT) - OVERLAY_HEIGHT
+            }
         };
         return Some((x, y));
     }
@@ -0,0 +1,260 @@
+use crate::dictionary::{Dictionary, DictionaryEntry};
+use crate::settings::{self, AppSettings, FieldError, SettingsError};
+use crate::shortcuts::{parse_binding_keys, LastTranscriptShortcutKeys, RecordShortcutKeys};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use utoipa::ToSchema;
+
+/// Name of the profile created for settings written before profiles existed.
+pub const DEFAULT_PROFILE: &str = "Default";
+
+/// What happens to the text of a dictation once it is transcribed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMethod {
+    /// Paste into the focused window, then restore the clipboard
+    #[default]
+    Paste,
+    /// Leave the text in the clipboard
+    Clipboard,
+    /// Only save it to history
+    None,
+}
+
+/// Find-and-replace applied after dictionary correction, in order.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, ToSchema)]
+#[serde(default)]
+pub struct PostProcessingRule {
+    pub find: String,
+    pub replace: String,
+    /// `find` is a regular expression and `replace` may refer to its groups as `$1`
+    pub regex: bool,
+}
+
+/// Everything that changes with the context of a dictation, e.g. code comments in English
+/// versus customer emails in French.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, ToSchema)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    pub dictionary: Vec<DictionaryEntry>,
+    pub record_shortcut: String,
+    pub last_transcript_shortcut: String,
+    pub post_processing: Vec<PostProcessingRule>,
+    pub output_method: OutputMethod,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+            dictionary: Vec::new(),
+            record_shortcut: "ctrl+space".to_string(),
+            last_transcript_shortcut: "ctrl+shift+space".to_string(),
+            post_processing: Vec::new(),
+            output_method: OutputMethod::Paste,
+        }
+    }
+}
+
+impl Profile {
+    /// Problems with this profile, as messages prefixed with its name.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.name.trim().is_empty() {
+            errors.push("A profile has no name".to_string());
+        }
+        if parse_binding_keys(&self.record_shortcut).is_empty() {
+            errors.push(format!("Profile '{}': invalid record shortcut", self.name));
+        }
+        if parse_binding_keys(&self.last_transcript_shortcut).is_empty() {
+            errors.push(format!(
+                "Profile '{}': invalid last transcript shortcut",
+                self.name
+            ));
+        }
+        for entry in &self.dictionary {
+            errors.extend(
+                entry
+                    .validate()
+                    .into_iter()
+                    .map(|e| format!("Profile '{}': {}", self.name, e)),
+            );
+        }
+        for rule in &self.post_processing {
+            if rule.find.is_empty() {
+                errors.push(format!(
+                    "Profile '{}': a rule has nothing to find",
+                    self.name
+                ));
+            } else if rule.regex {
+                if let Err(e) = Regex::new(&rule.find) {
+                    errors.push(format!("Profile '{}': {}", self.name, e));
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// The profiles and which one is active, as returned to the UI and the HTTP API.
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
+pub struct ProfileList {
+    pub profiles: Vec<Profile>,
+    pub active_profile: String,
+}
+
+impl From<AppSettings> for ProfileList {
+    fn from(s: AppSettings) -> Self {
+        Self {
+            profiles: s.profiles,
+            active_profile: s.active_profile,
+        }
+    }
+}
+
+/// Apply `rules` in order. Invalid regular expressions are skipped, validation rejects them.
+pub fn apply_rules(text: &str, rules: &[PostProcessingRule]) -> String {
+    rules.iter().fold(text.to_string(), |text, rule| {
+        if rule.find.is_empty() {
+            return text;
+        }
+        if !rule.regex {
+            return text.replace(&rule.find, &rule.replace);
+        }
+        match Regex::new(&rule.find) {
+            Ok(re) => re.replace_all(&text, rule.replace.as_str()).into_owned(),
+            Err(_) => text,
+        }
+    })
+}
+
+/// Apply the post-processing rules of the active profile.
+pub fn post_process(app: &AppHandle, text: String) -> String {
+    let s = settings::load_settings(app);
+    apply_rules(&text, &s.active_profile().post_processing)
+}
+
+/// Push the active profile to the state used by the shortcut listener and the dictionary
+/// correction. All locks are taken first, so a dictation never sees half of a switch.
+pub fn apply_active_profile(app: &AppHandle, settings: &AppSettings) {
+    let (Some(dictionary), Some(record_keys), Some(last_transcript_keys)) = (
+        app.try_state::<Dictionary>(),
+        app.try_state::<RecordShortcutKeys>(),
+        app.try_state::<LastTranscriptShortcutKeys>(),
+    ) else {
+        return;
+    };
+    let profile = settings.active_profile();
+
+    let mut dictionary = dictionary.0.lock().unwrap();
+    let mut record_keys = record_keys.0.lock().unwrap();
+    let mut last_transcript_keys = last_transcript_keys.0.lock().unwrap();
+    *dictionary = profile.dictionary.clone();
+    *record_keys = parse_binding_keys(&profile.record_shortcut);
+    *last_transcript_keys = parse_binding_keys(&profile.last_transcript_shortcut);
+}
+
+pub fn switch_profile(app: &AppHandle, name: &str) -> Result<AppSettings, SettingsError> {
+    let s = settings::load_settings(app);
+    if !s.profiles.iter().any(|profile| profile.name == name) {
+        return Err(SettingsError::not_found(FieldError::new(
+            "active_profile",
+            format!("No profile named '{}'", name),
+        )));
+    }
+    let s = settings::update_settings(app, |s| s.active_profile = name.to_string())?;
+    apply_active_profile(app, &s);
+    println!("Switched to profile {}", name);
+    Ok(s)
+}
+
+/// Switch to the profile after the active one, used by the profile shortcut.
+pub fn switch_to_next_profile(app: &AppHandle) -> Result<AppSettings, SettingsError> {
+    let s = settings::load_settings(app);
+    if s.profiles.is_empty() {
+        return Err(FieldError::new("profiles", "There is no profile to switch to").into());
+    }
+    let index = s
+        .profiles
+        .iter()
+        .position(|profile| profile.name == s.active_profile)
+        .unwrap_or(0);
+    let next = &s.profiles[(index + 1) % s.profiles.len()];
+    switch_profile(app, &next.name)
+}
+
+/// Add a profile named `name`, starting as a copy of the active one.
+pub fn create_profile(app: &AppHandle, name: &str) -> Result<AppSettings, SettingsError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(FieldError::new("profiles", "A profile needs a name").into());
+    }
+    if settings::load_settings(app)
+        .profiles
+        .iter()
+        .any(|profile| profile.name == name)
+    {
+        return Err(
+            FieldError::new("profiles", format!("Profile '{}' already exists", name)).into(),
+        );
+    }
+    settings::update_settings(app, |s| {
+        let profile = Profile {
+            name,
+            ..s.active_profile().clone()
+        };
+        s.profiles.push(profile);
+    })
+}
+
+pub fn rename_profile(
+    app: &AppHandle,
+    name: &str,
+    new_name: &str,
+) -> Result<AppSettings, SettingsError> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err(FieldError::new("profiles", "A profile needs a name").into());
+    }
+    let s = settings::load_settings(app);
+    if !s.profiles.iter().any(|profile| profile.name == name) {
+        return Err(FieldError::new("profiles", format!("No profile named '{}'", name)).into());
+    }
+    if name != new_name && s.profiles.iter().any(|profile| profile.name == new_name) {
+        return Err(
+            FieldError::new("profiles", format!("Profile '{}' already exists", new_name)).into(),
+        );
+    }
+    settings::update_settings(app, |s| {
+        if s.active_profile == name {
+            s.active_profile = new_name.clone();
+        }
+        if let Some(profile) = s.profiles.iter_mut().find(|profile| profile.name == name) {
+            profile.name = new_name;
+        }
+    })
+}
+
+/// Delete a profile, the first remaining one becomes active if it was the active one.
+pub fn delete_profile(app: &AppHandle, name: &str) -> Result<AppSettings, SettingsError> {
+    let s = settings::load_settings(app);
+    if !s.profiles.iter().any(|profile| profile.name == name) {
+        return Err(SettingsError::not_found(FieldError::new(
+            "profiles",
+            format!("No profile named '{}'", name),
+        )));
+    }
+    if s.profiles.len() <= 1 {
+        return Err(FieldError::new("profiles", "The last profile cannot be deleted").into());
+    }
+    let s = settings::update_settings(app, |s| {
+        s.profiles.retain(|profile| profile.name != name);
+        if s.active_profile == name {
+            s.active_profile = s.profiles[0].name.clone();
+        }
+    })?;
+    apply_active_profile(app, &s);
+    Ok(s)
+}
@@ -1,33 +1,565 @@
+use crate::http_api::HttpApiState;
+use crate::profiles::{Profile, DEFAULT_PROFILE};
+use crate::shortcuts::{parse_binding_keys, ProfileShortcutKeys};
+use crate::storage;
+use crate::webhooks::WebhookConfig;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
-use tauri::{AppHandle, Manager};
+use serde_json::{json, Map, Value};
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    fmt, fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Layout version of `settings.json`, bump it together with a new entry in `MIGRATIONS`.
+pub const SETTINGS_VERSION: u32 = 4;
+
+/// Upgrade steps, `MIGRATIONS[n]` turns a version `n + 1` file into version `n + 2`.
+/// Files written before versioning have no `version` field and count as version 1.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] =
+    &[migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4];
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(default)]
 pub struct AppSettings {
-    pub record_shortcut: String,
-    pub last_transcript_shortcut: String,
-    pub dictionary: Vec<String>,
-    pub overlay_mode: String,     // "hidden" | "recording" | "always"
-    pub overlay_position: String, // "top" | "bottom"
+    pub version: u32,
+    /// Dictionary, shortcuts and output of each context, see `active_profile()`
+    pub profiles: Vec<Profile>,
+    pub active_profile: String,
+    pub profile_shortcut: String, // Switches to the next profile, empty when disabled
+    pub overlay_mode: OverlayMode,
+    pub overlay_position: OverlayPosition,
     pub api_enabled: bool,        // Enable local HTTP API
     pub api_port: u16,            // Port for local HTTP API
+    pub api_key: String,          // Bearer token required by the local HTTP API
+    pub api_cors_origins: Vec<String>, // Browser origins allowed to call the local HTTP API
+    pub api_bind_address: String, // Interface the local HTTP API listens on
+    pub api_allow_external_bind: bool, // Required to bind on a non-loopback interface
+    pub api_socket_path: String,  // Unix domain socket path, replaces TCP when set
+    pub api_max_queued_jobs: usize, // Jobs waiting in /api/jobs before answering 429
+    pub webhooks: Vec<WebhookConfig>, // Called after each dictation
+    pub history_retention_days: u32, // Delete history entries older than this, 0 keeps them forever
+    pub history_max_entries: usize, // Keep at most this many history entries, 0 for no limit
+    pub keep_recordings: bool, // Keep the audio of each dictation, linked to its history entry
+    pub recordings_max_count: usize, // Keep at most this many recordings, 0 for no limit
+    pub recordings_retention_days: u32, // Delete recordings older than this, 0 keeps them forever
+    pub recordings_max_size_mb: u64, // Total size of kept recordings, 0 for no limit
+    /// Fields written by a newer version of Murmure, kept as is so a downgrade doesn't drop them
+    #[serde(flatten)]
+    pub unknown: Map<String, Value>,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
-            record_shortcut: "ctrl+space".to_string(),
-            last_transcript_shortcut: "ctrl+shift+space".to_string(),
-            dictionary: Vec::new(),
-            overlay_mode: "recording".to_string(),
-            overlay_position: "bottom".to_string(),
+            version: SETTINGS_VERSION,
+            profiles: vec![Profile::default()],
+            active_profile: DEFAULT_PROFILE.to_string(),
+            profile_shortcut: String::new(),
+            overlay_mode: OverlayMode::Recording,
+            overlay_position: OverlayPosition::Bottom,
             api_enabled: false,
             api_port: 4800,
+            api_key: String::new(),
+            api_cors_origins: Vec::new(),
+            api_bind_address: "127.0.0.1".to_string(),
+            api_allow_external_bind: false,
+            api_socket_path: String::new(),
+            api_max_queued_jobs: 32,
+            webhooks: Vec::new(),
+            history_retention_days: 0,
+            history_max_entries: 0,
+            keep_recordings: false,
+            recordings_max_count: 100,
+            recordings_retention_days: 30,
+            recordings_max_size_mb: 500,
+            unknown: Map::new(),
+        }
+    }
+}
+
+/// When the recording overlay is visible.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayMode {
+    Hidden,
+    Recording,
+    Always,
+}
+
+impl FromStr for OverlayMode {
+    type Err = FieldError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "hidden" => Ok(OverlayMode::Hidden),
+            "recording" => Ok(OverlayMode::Recording),
+            "always" => Ok(OverlayMode::Always),
+            _ => Err(FieldError::new(
+                "overlay_mode",
+                "Must be one of hidden, recording or always",
+            )),
+        }
+    }
+}
+
+/// Where the recording overlay is shown on the primary monitor.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayPosition {
+    Top,
+    Bottom,
+}
+
+impl FromStr for OverlayPosition {
+    type Err = FieldError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "top" => Ok(OverlayPosition::Top),
+            "bottom" => Ok(OverlayPosition::Bottom),
+            _ => Err(FieldError::new("overlay_position", "Must be top or bottom")),
+        }
+    }
+}
+
+/// A setting that was refused, `field` is its name in `settings.json`.
+#[derive(Serialize, Deserialize, Clone, Debug, utoipa::ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Error returned by settings commands, `fields` lists the invalid values when validation failed.
+#[derive(Serialize, Debug)]
+pub struct SettingsError {
+    pub message: String,
+    pub fields: Vec<FieldError>,
+    /// The value names something that does not exist, such as an unknown profile
+    #[serde(skip)]
+    pub not_found: bool,
+}
+
+impl SettingsError {
+    pub fn not_found(error: FieldError) -> Self {
+        Self {
+            not_found: true,
+            ..Self::invalid(vec![error])
+        }
+    }
+
+    pub fn invalid(fields: Vec<FieldError>) -> Self {
+        let message = fields
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Self {
+            message: format!("Invalid settings ({})", message),
+            fields,
+            not_found: false,
+        }
+    }
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for SettingsError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            fields: Vec::new(),
+            not_found: false,
         }
     }
 }
 
+impl From<FieldError> for SettingsError {
+    fn from(error: FieldError) -> Self {
+        Self::invalid(vec![error])
+    }
+}
+
+impl From<SettingsError> for String {
+    fn from(error: SettingsError) -> Self {
+        error.message
+    }
+}
+
+impl AppSettings {
+    /// Every invalid field, empty when the settings can be saved.
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if self.profiles.is_empty() {
+            errors.push(FieldError::new("profiles", "At least one profile is required"));
+        }
+        for (index, profile) in self.profiles.iter().enumerate() {
+            if self.profiles[..index].iter().any(|other| other.name == profile.name) {
+                errors.push(FieldError::new(
+                    "profiles",
+                    format!("Profile '{}' exists twice", profile.name),
+                ));
+            }
+            for message in profile.validate() {
+                errors.push(FieldError::new("profiles", message));
+            }
+        }
+        if !self
+            .profiles
+            .iter()
+            .any(|profile| profile.name == self.active_profile)
+        {
+            errors.push(FieldError::new(
+                "active_profile",
+                format!("No profile named '{}'", self.active_profile),
+            ));
+        }
+        if !self.profile_shortcut.trim().is_empty()
+            && parse_binding_keys(&self.profile_shortcut).is_empty()
+        {
+            errors.push(FieldError::new("profile_shortcut", "Invalid shortcut"));
+        }
+        if self.api_port < 1024 {
+            errors.push(FieldError::new("api_port", "Port must be >= 1024"));
+        }
+        match self.api_bind_address.trim().parse::<IpAddr>() {
+            Err(_) => errors.push(FieldError::new(
+                "api_bind_address",
+                format!("Invalid address '{}'", self.api_bind_address),
+            )),
+            // Reported on both fields, whichever of the two was just changed
+            Ok(ip) if !ip.is_loopback() && !self.api_allow_external_bind => {
+                let message = format!(
+                    "{} is reachable from other machines, api_allow_external_bind must be enabled",
+                    ip
+                );
+                errors.push(FieldError::new("api_bind_address", message.clone()));
+                errors.push(FieldError::new("api_allow_external_bind", message));
+            }
+            Ok(_) => {}
+        }
+        let socket_path = self.api_socket_path.trim();
+        if !socket_path.is_empty() {
+            if cfg!(not(unix)) {
+                errors.push(FieldError::new(
+                    "api_socket_path",
+                    "Unix domain sockets are not supported on this platform",
+                ));
+            } else if !Path::new(socket_path).is_absolute() {
+                errors.push(FieldError::new(
+                    "api_socket_path",
+                    format!("'{}' is not an absolute path", socket_path),
+                ));
+            }
+        }
+        if let Some(origin) = self
+            .api_cors_origins
+            .iter()
+            .find(|origin| reqwest::Url::parse(origin).is_err())
+        {
+            errors.push(FieldError::new(
+                "api_cors_origins",
+                format!("Invalid origin '{}'", origin),
+            ));
+        }
+        if self.api_max_queued_jobs == 0 {
+            errors.push(FieldError::new("api_max_queued_jobs", "Must be at least 1"));
+        }
+        if let Some(webhook) = self
+            .webhooks
+            .iter()
+            .find(|webhook| webhook.enabled && reqwest::Url::parse(&webhook.url).is_err())
+        {
+            errors.push(FieldError::new(
+                "webhooks",
+                format!("Invalid webhook URL '{}'", webhook.url),
+            ));
+        }
+        errors
+    }
+}
+
+/// One top-level field that changed, with its values as they appear in `settings.json`.
+#[derive(Serialize, Clone, Debug)]
+pub struct SettingsChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Fields that differ between `before` and `after`.
+pub fn diff_settings(before: &AppSettings, after: &AppSettings) -> Vec<SettingsChange> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+    let mut changes: Vec<SettingsChange> = after
+        .iter()
+        .filter(|(field, value)| before.get(*field) != Some(*value))
+        .map(|(field, value)| SettingsChange {
+            field: field.clone(),
+            old: before.get(field).cloned().unwrap_or(Value::Null),
+            new: value.clone(),
+        })
+        .collect();
+    changes.extend(
+        before
+            .iter()
+            .filter(|(field, _)| !after.contains_key(*field))
+            .map(|(field, value)| SettingsChange {
+                field: field.clone(),
+                old: value.clone(),
+                new: Value::Null,
+            }),
+    );
+    changes
+}
+
+/// Overlay values were free-form strings, normalize them and drop the ones that never worked.
+fn migrate_v1_to_v2(settings: &mut Map<String, Value>) {
+    for (field, allowed) in [
+        ("overlay_mode", &["hidden", "recording", "always"][..]),
+        ("overlay_position", &["top", "bottom"][..]),
+    ] {
+        let Some(value) = settings.get(field) else {
+            continue;
+        };
+        let normalized = value.as_str().map(|value| value.trim().to_lowercase());
+        match normalized {
+            Some(value) if allowed.contains(&value.as_str()) => {
+                settings.insert(field.to_string(), Value::String(value));
+            }
+            _ => {
+                eprintln!("Resetting invalid {} {} to its default", field, value);
+                settings.remove(field);
+            }
+        }
+    }
+}
+
+/// Fields of version 2 that moved into profiles.
+const PROFILE_FIELDS_V2: [&str; 3] = ["dictionary", "record_shortcut", "last_transcript_shortcut"];
+
+/// The dictionary and shortcuts moved into profiles, they become the default profile.
+fn migrate_v2_to_v3(settings: &mut Map<String, Value>) {
+    let mut profile = Map::new();
+    profile.insert("name".to_string(), Value::from(DEFAULT_PROFILE));
+    for field in PROFILE_FIELDS_V2 {
+        if let Some(value) = settings.remove(field) {
+            profile.insert(field.to_string(), value);
+        }
+    }
+    // Nothing to carry over, the default profile comes from `AppSettings::default`
+    if profile.len() == 1 {
+        return;
+    }
+    settings.insert("profiles".to_string(), Value::Array(vec![Value::Object(profile)]));
+    settings.insert("active_profile".to_string(), Value::from(DEFAULT_PROFILE));
+}
+
+/// Dictionary words became entries, with the languages they were always matched in.
+fn migrate_v3_to_v4(settings: &mut Map<String, Value>) {
+    let Some(Value::Array(profiles)) = settings.get_mut("profiles") else {
+        return;
+    };
+    for profile in profiles {
+        let Some(Value::Array(words)) = profile.get_mut("dictionary") else {
+            continue;
+        };
+        for word in words.iter_mut() {
+            if let Value::String(canonical) = word {
+                *word = json!({ "canonical": canonical });
+            }
+        }
+    }
+}
+
+/// Bring a parsed `settings.json` up to `SETTINGS_VERSION`, returns whether anything ran.
+fn migrate_settings(settings: &mut Map<String, Value>) -> bool {
+    let version = settings
+        .get("version")
+        .and_then(Value::as_u64)
+        .unwrap_or(1) as u32;
+    if version > SETTINGS_VERSION {
+        eprintln!(
+            "settings.json was written by a newer version of Murmure (version {}), unknown fields are kept as is",
+            version
+        );
+        return false;
+    }
+    if version == SETTINGS_VERSION {
+        return false;
+    }
+    for (index, migration) in MIGRATIONS
+        .iter()
+        .enumerate()
+        .skip(version.saturating_sub(1) as usize)
+    {
+        println!("Migrating settings to version {}", index + 2);
+        migration(settings);
+    }
+    settings.insert("version".to_string(), Value::from(SETTINGS_VERSION));
+    true
+}
+
+/// Deserialize migrated settings, falling back to the default for each field that doesn't fit
+/// instead of discarding the whole file. Returns whether a field was reset.
+fn settings_from_map(mut settings: Map<String, Value>) -> (AppSettings, bool) {
+    let error = match serde_json::from_value(Value::Object(settings.clone())) {
+        Ok(parsed) => return (parsed, false),
+        Err(e) => e,
+    };
+    eprintln!("Some settings are invalid: {}", error);
+
+    let Ok(Value::Object(defaults)) = serde_json::to_value(AppSettings::default()) else {
+        return (AppSettings::default(), true);
+    };
+    settings.retain(|field, value| {
+        let mut probe = defaults.clone();
+        probe.insert(field.clone(), value.clone());
+        let valid = serde_json::from_value::<AppSettings>(Value::Object(probe)).is_ok();
+        if !valid {
+            eprintln!("Resetting invalid {} {} to its default", field, value);
+        }
+        valid
+    });
+    (
+        serde_json::from_value(Value::Object(settings)).unwrap_or_default(),
+        true,
+    )
+}
+
+/// Overlay the fields of an imported bundle on `current`, as if they had been written to
+/// `settings.json`. Unlike a damaged file, a field that doesn't fit is refused, not reset.
+pub fn merge_settings(
+    current: &AppSettings,
+    mut fields: Map<String, Value>,
+) -> Result<AppSettings, SettingsError> {
+    // A hand-written bundle usually leaves the version out, it targets this version
+    let version = fields
+        .entry("version")
+        .or_insert_with(|| Value::from(SETTINGS_VERSION))
+        .as_u64()
+        .unwrap_or(1);
+    let before_profiles = version < 3;
+    if before_profiles && (fields.contains_key("profiles") || fields.contains_key("active_profile"))
+    {
+        return Err(SettingsError::invalid(vec![FieldError::new(
+            "version",
+            format!(
+                "Profiles need a bundle of version 3 or later, this one is version {}",
+                version
+            ),
+        )]));
+    }
+    migrate_settings(&mut fields);
+    fields.insert("version".to_string(), Value::from(SETTINGS_VERSION));
+
+    let Ok(Value::Object(mut merged)) = serde_json::to_value(current) else {
+        return Err("Failed to read the current settings".to_string().into());
+    };
+    // The dictionary and shortcuts of an older bundle update the profile they came from, the
+    // other profiles and the active one stay as they are
+    if before_profiles {
+        fields.remove("active_profile");
+        if let Some(Value::Array(migrated)) = fields.remove("profiles") {
+            let mut profiles = match merged.get("profiles") {
+                Some(Value::Array(profiles)) => profiles.clone(),
+                _ => Vec::new(),
+            };
+            for profile in migrated {
+                let Value::Object(profile) = profile else {
+                    continue;
+                };
+                let existing = profiles
+                    .iter_mut()
+                    .filter_map(Value::as_object_mut)
+                    .find(|existing| existing.get("name") == profile.get("name"));
+                match existing {
+                    Some(existing) => existing.extend(profile),
+                    None => profiles.push(Value::Object(profile)),
+                }
+            }
+            fields.insert("profiles".to_string(), Value::Array(profiles));
+        }
+    }
+    let errors: Vec<FieldError> = fields
+        .iter()
+        .filter_map(|(field, value)| {
+            let mut probe = merged.clone();
+            probe.insert(field.clone(), value.clone());
+            serde_json::from_value::<AppSettings>(Value::Object(probe))
+                .err()
+                .map(|e| FieldError::new(field, e.to_string()))
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(SettingsError::invalid(errors));
+    }
+
+    let names_active_profile = fields.contains_key("active_profile");
+    merged.extend(fields);
+    let mut settings: AppSettings =
+        serde_json::from_value(Value::Object(merged)).map_err(|e| e.to_string())?;
+    // New profiles without the active one named: start on the first of them
+    let active_exists = settings
+        .profiles
+        .iter()
+        .any(|profile| profile.name == settings.active_profile);
+    if !names_active_profile && !active_exists {
+        if let Some(first) = settings.profiles.first() {
+            settings.active_profile = first.name.clone();
+        }
+    }
+    Ok(settings)
+}
+
+impl AppSettings {
+    /// The profile in use, the first one when `active_profile` names none of them.
+    pub fn active_profile(&self) -> &Profile {
+        self.profiles
+            .iter()
+            .find(|profile| profile.name == self.active_profile)
+            .or(self.profiles.first())
+            .unwrap_or(&DEFAULT_PROFILE_SETTINGS)
+    }
+
+    pub fn active_profile_mut(&mut self) -> &mut Profile {
+        if self.profiles.is_empty() {
+            self.profiles.push(Profile::default());
+        }
+        let index = self
+            .profiles
+            .iter()
+            .position(|profile| profile.name == self.active_profile)
+            .unwrap_or(0);
+        &mut self.profiles[index]
+    }
+}
+
+// Returned by `active_profile()` when a hand-edited file has no profile at all
+static DEFAULT_PROFILE_SETTINGS: Lazy<Profile> = Lazy::new(Profile::default);
+
 fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
     let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
     if let Err(e) = fs::create_dir_all(&dir) {
@@ -36,24 +568,514 @@ fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(dir.join("settings.json"))
 }
 
+// Held across every read and write, so concurrent updates cannot overwrite each other
+static SETTINGS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Keep every settings read and write waiting, while the files are re-encrypted.
+pub fn lock_settings() -> parking_lot::MutexGuard<'static, ()> {
+    SETTINGS_LOCK.lock()
+}
+
+/// What caused a `settings-changed` event.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeSource {
+    /// A command of the app
+    App,
+    /// `settings.json` was edited outside the app, or could only be read after unlocking
+    File,
+}
+
+/// Payload of the `settings-changed` event.
+#[derive(Serialize, Clone, Debug)]
+pub struct SettingsChanged {
+    pub source: ChangeSource,
+    pub changes: Vec<SettingsChange>,
+}
+
+/// Parsed settings, kept in memory so reading them doesn't touch the disk.
+/// Every update is written through to `settings.json`, and external edits of the file are
+/// picked up by a watcher.
+pub struct SettingsStore {
+    current: RwLock<AppSettings>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl SettingsStore {
+    /// Read `settings.json`, once encryption is initialized.
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            current: RwLock::new(read_settings_from_disk(app)),
+            watcher: Mutex::new(None),
+        }
+    }
+
+    pub fn get(&self) -> AppSettings {
+        self.current.read().clone()
+    }
+
+    /// Reload the settings whenever `settings.json` changes on disk.
+    pub fn watch(&self, app: &AppHandle) -> Result<(), String> {
+        let path = settings_path(app)?;
+        let dir = path.parent().ok_or("Invalid settings path")?.to_path_buf();
+        let (sender, receiver) = std::sync::mpsc::channel::<notify::Result<notify::Event>>();
+
+        // Saves replace the file through a rename, so watch the directory rather than the file
+        let mut watcher = notify::recommended_watcher(sender).map_err(|e| e.to_string())?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| e.to_string())?;
+        *self.watcher.lock() = Some(watcher);
+
+        let app = app.clone();
+        std::thread::spawn(move || {
+            // Compare names only, the watcher may report the directory through another path
+            let concerns_settings = |event: &notify::Result<notify::Event>| {
+                event.as_ref().is_ok_and(|event| {
+                    event
+                        .paths
+                        .iter()
+                        .any(|changed| changed.file_name() == path.file_name())
+                })
+            };
+            while let Ok(event) = receiver.recv() {
+                if !concerns_settings(&event) {
+                    continue;
+                }
+                // Editors often write in several steps, wait for them to settle
+                while receiver.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+                if let Err(e) = reload_settings(&app) {
+                    eprintln!("Ignoring change of settings.json: {}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Quiet period after the last change of `settings.json` before reloading it.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
 pub fn load_settings(app: &AppHandle) -> AppSettings {
+    match app.try_state::<SettingsStore>() {
+        Some(store) => store.get(),
+        // Only during startup, before the store is managed
+        None => read_settings_from_disk(app),
+    }
+}
+
+fn read_settings_from_disk(app: &AppHandle) -> AppSettings {
     let path = match settings_path(app) {
         Ok(p) => p,
         Err(_) => return AppSettings::default(),
     };
 
-    match fs::read_to_string(&path) {
-        Ok(content) => serde_json::from_str::<AppSettings>(&content).unwrap_or_default(),
-        Err(_) => {
-            let defaults = AppSettings::default();
-            let _ = save_settings(app, &defaults);
-            defaults
+    let _guard = SETTINGS_LOCK.lock();
+    read_settings(app, &path)
+}
+
+/// Load, modify and save the settings without another update slipping in between.
+/// Only the fields changed by `update` are validated, so a bad value left in the file by hand
+/// doesn't block every other setting.
+pub fn update_settings<F>(app: &AppHandle, update: F) -> Result<AppSettings, SettingsError>
+where
+    F: FnOnce(&mut AppSettings),
+{
+    try_update_settings(app, |s| {
+        update(s);
+        Ok(())
+    })
+}
+
+/// `update_settings` with an update that may fail, nothing is saved when it does.
+pub fn try_update_settings<F>(app: &AppHandle, update: F) -> Result<AppSettings, SettingsError>
+where
+    F: FnOnce(&mut AppSettings) -> Result<(), SettingsError>,
+{
+    let path = settings_path(app)?;
+    let (settings, changes) = {
+        let _guard = SETTINGS_LOCK.lock();
+        if crate::encryption::is_locked() {
+            return Err("Settings are encrypted, unlock them first".to_string().into());
+        }
+        let before = match app.try_state::<SettingsStore>() {
+            Some(store) => store.get(),
+            None => read_settings(app, &path),
+        };
+        let mut settings = before.clone();
+        update(&mut settings)?;
+
+        let changes = diff_settings(&before, &settings);
+        let errors: Vec<FieldError> = settings
+            .validate()
+            .into_iter()
+            .filter(|e| changes.iter().any(|change| change.field == e.field))
+            .collect();
+        if !errors.is_empty() {
+            return Err(SettingsError::invalid(errors));
+        }
+
+        write_settings(&path, &settings)?;
+        if let Some(store) = app.try_state::<SettingsStore>() {
+            *store.current.write() = settings.clone();
+        }
+        (settings, changes)
+    };
+
+    notify_changes(app, ChangeSource::App, changes);
+    Ok(settings)
+}
+
+/// Read `settings.json` again and apply what changed, after an external edit or an unlock.
+/// A file that cannot be parsed is left alone, it may still be being written.
+pub fn reload_settings(app: &AppHandle) -> Result<(), String> {
+    let path = settings_path(app)?;
+    let store = app
+        .try_state::<SettingsStore>()
+        .ok_or("Settings are not loaded yet")?;
+    let changes = {
+        let _guard = SETTINGS_LOCK.lock();
+        let (settings, outdated) =
+            parse_settings_file(&path).map_err(|e| format!("{:#}", e))?;
+        if outdated {
+            write_settings(&path, &settings)?;
         }
+        let mut current = store.current.write();
+        let changes = diff_settings(&current, &settings);
+        *current = settings;
+        changes
+    };
+
+    if !changes.is_empty() {
+        apply_runtime_settings(app, &changes);
     }
+    notify_changes(app, ChangeSource::File, changes);
+    Ok(())
 }
 
-pub fn save_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
-    let path = settings_path(app)?;
+/// Bring the state derived from the settings in line after they changed outside a command.
+pub fn apply_runtime_settings(app: &AppHandle, changes: &[SettingsChange]) {
+    let s = load_settings(app);
+    for change in changes {
+        match change.field.as_str() {
+            "profiles" | "active_profile" => crate::profiles::apply_active_profile(app, &s),
+            "profile_shortcut" => {
+                if let Some(keys) = app.try_state::<ProfileShortcutKeys>() {
+                    keys.set(parse_binding_keys(&s.profile_shortcut));
+                }
+            }
+            "overlay_mode" => match s.overlay_mode {
+                OverlayMode::Always => crate::overlay::show_recording_overlay(app),
+                OverlayMode::Hidden | OverlayMode::Recording => {
+                    crate::overlay::hide_recording_overlay(app)
+                }
+            },
+            "overlay_position" => crate::overlay::update_overlay_position(app),
+            _ => {}
+        }
+    }
+
+    // The key and queue depth are read on every request, everything else needs a restart
+    let restart_api = changes.iter().any(|change| {
+        change.field.starts_with("api_")
+            && !matches!(change.field.as_str(), "api_key" | "api_max_queued_jobs")
+    });
+    if restart_api {
+        if let Some(api) = app.try_state::<HttpApiState>() {
+            api.apply();
+        }
+    }
+}
+
+fn notify_changes(app: &AppHandle, source: ChangeSource, changes: Vec<SettingsChange>) {
+    if changes.is_empty() {
+        return;
+    }
+    let _ = app.emit("settings-changed", SettingsChanged { source, changes });
+}
+
+/// Parse a settings file, also returns whether it should be rewritten because it was migrated
+/// or had fields reset.
+fn parse_settings_file(path: &Path) -> anyhow::Result<(AppSettings, bool)> {
+    let content = crate::encryption::read_file(path)?;
+    match serde_json::from_slice(&content)? {
+        Value::Object(mut settings) => {
+            let migrated = migrate_settings(&mut settings);
+            let (settings, reset) = settings_from_map(settings);
+            Ok((settings, migrated || reset))
+        }
+        _ => Err(anyhow::anyhow!("settings are not a JSON object")),
+    }
+}
+
+fn read_settings(app: &AppHandle, path: &Path) -> AppSettings {
+    if !path.exists() {
+        let defaults = AppSettings::default();
+        let _ = write_settings(path, &defaults);
+        return defaults;
+    }
+
+    match parse_settings_file(path) {
+        Ok((settings, outdated)) => {
+            // The previous file is kept as the backup
+            if outdated {
+                if let Err(e) = write_settings(path, &settings) {
+                    eprintln!("Failed to save migrated settings: {}", e);
+                }
+            }
+            settings
+        }
+        // Not damaged, only waiting for the passphrase
+        Err(_) if crate::encryption::is_locked() => AppSettings::default(),
+        Err(e) => {
+            eprintln!("Failed to load settings: {:#}", e);
+            recover_settings(app, path)
+        }
+    }
+}
+
+/// Replace a damaged settings file with its backup, or with the defaults.
+fn recover_settings(app: &AppHandle, path: &Path) -> AppSettings {
+    let quarantined = match storage::quarantine(path) {
+        Ok(target) => Some(target.display().to_string()),
+        Err(e) => {
+            eprintln!("Failed to move {} aside: {}", path.display(), e);
+            None
+        }
+    };
+
+    let backup = parse_settings_file(&storage::backup_path(path))
+        .ok()
+        .map(|(settings, _)| settings);
+    let restored_from_backup = backup.is_some();
+    let settings = backup.unwrap_or_default();
+    if let Err(e) = write_settings(path, &settings) {
+        eprintln!("Failed to write recovered settings: {}", e);
+    }
+
+    storage::report_recovery(
+        app,
+        storage::Recovery {
+            file: "settings.json".to_string(),
+            quarantined,
+            restored_from_backup,
+        },
+    );
+    settings
+}
+
+fn write_settings(path: &Path, settings: &AppSettings) -> Result<(), String> {
     let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(path, content).map_err(|e| e.to_string())
+
+    // Keep the previous version as long as it is readable, to recover from a damaged file
+    let readable = crate::encryption::read_file(path)
+        .ok()
+        .and_then(|content| serde_json::from_slice::<Value>(&content).ok())
+        .is_some_and(|value| value.is_object());
+    if readable {
+        if let Err(e) = fs::copy(path, storage::backup_path(path)) {
+            eprintln!("Failed to back up settings: {}", e);
+        }
+    }
+
+    crate::encryption::write_file(path, content.as_bytes()).map_err(|e| format!("{:#}", e))
+}
+
+pub fn generate_api_key() -> String {
+    format!(
+        "mur_{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Return the API key, generating and saving one on first use.
+pub fn ensure_api_key(app: &AppHandle) -> Result<String, String> {
+    let s = load_settings(app);
+    if !s.api_key.is_empty() {
+        return Ok(s.api_key);
+    }
+    let s = update_settings(app, |s| {
+        // Another caller may have generated it in the meantime
+        if s.api_key.is_empty() {
+            s.api_key = generate_api_key();
+        }
+    })?;
+    Ok(s.api_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::{DictionaryEntry, MatchStrictness};
+
+    fn migrate(settings: Value) -> AppSettings {
+        let Value::Object(mut settings) = settings else {
+            panic!("settings must be an object");
+        };
+        assert!(migrate_settings(&mut settings));
+        let (settings, _) = settings_from_map(settings);
+        settings
+    }
+
+    #[test]
+    fn v3_dictionary_words_become_entries() {
+        let settings = migrate(json!({
+            "version": 3,
+            "active_profile": "Default",
+            "profiles": [{ "name": "Default", "dictionary": ["Murmure", "Kubernetes operator"] }],
+        }));
+
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(
+            settings.profiles[0].dictionary,
+            vec![
+                DictionaryEntry {
+                    canonical: "Murmure".to_string(),
+                    ..Default::default()
+                },
+                DictionaryEntry {
+                    canonical: "Kubernetes operator".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+        // Matched like before, in French and English
+        let entry = &settings.profiles[0].dictionary[0];
+        assert_eq!(entry.languages, ["french", "english"]);
+        assert_eq!(entry.strictness, MatchStrictness::Loose);
+        assert!(entry.aliases.is_empty());
+        assert!(!entry.case_sensitive);
+    }
+
+    #[test]
+    fn v3_migration_keeps_entries_and_every_profile() {
+        let settings = migrate(json!({
+            "version": 3,
+            "active_profile": "Emails",
+            "profiles": [
+                { "name": "Default", "dictionary": ["Murmure"] },
+                {
+                    "name": "Emails",
+                    "dictionary": [
+                        "Zaragoza",
+                        { "canonical": "Stuttgart", "languages": ["german"] },
+                    ],
+                },
+            ],
+        }));
+
+        assert_eq!(settings.profiles.len(), 2);
+        assert_eq!(settings.profiles[0].dictionary[0].canonical, "Murmure");
+        let emails = &settings.profiles[1].dictionary;
+        assert_eq!(emails[0].canonical, "Zaragoza");
+        assert_eq!(emails[1].canonical, "Stuttgart");
+        assert_eq!(emails[1].languages, ["german"]);
+    }
+
+    fn field_errors(settings: &AppSettings, field: &str) -> Vec<String> {
+        settings
+            .validate()
+            .into_iter()
+            .filter(|e| e.field == field)
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn external_bind_requires_the_opt_in() {
+        let mut settings = AppSettings {
+            api_bind_address: "0.0.0.0".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(field_errors(&settings, "api_bind_address").len(), 1);
+        assert_eq!(field_errors(&settings, "api_allow_external_bind").len(), 1);
+
+        settings.api_allow_external_bind = true;
+        assert!(field_errors(&settings, "api_bind_address").is_empty());
+        assert!(field_errors(&settings, "api_allow_external_bind").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_path_must_be_absolute() {
+        let mut settings = AppSettings {
+            api_socket_path: "murmure.sock".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(field_errors(&settings, "api_socket_path").len(), 1);
+
+        settings.api_socket_path = "/run/user/1000/murmure.sock".to_string();
+        assert!(field_errors(&settings, "api_socket_path").is_empty());
+    }
+
+    fn current_with_profiles() -> AppSettings {
+        AppSettings {
+            profiles: vec![
+                Profile {
+                    dictionary: vec![DictionaryEntry {
+                        canonical: "Murmure".to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                Profile {
+                    name: "Emails".to_string(),
+                    record_shortcut: "ctrl+alt+e".to_string(),
+                    ..Default::default()
+                },
+            ],
+            active_profile: "Emails".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn bundle(fields: Value) -> Map<String, Value> {
+        let Value::Object(fields) = fields else {
+            panic!("bundle must be an object");
+        };
+        fields
+    }
+
+    #[test]
+    fn v2_bundle_without_profile_fields_keeps_every_profile() {
+        let current = current_with_profiles();
+        let merged = merge_settings(
+            &current,
+            bundle(json!({ "version": 2, "overlay_mode": "always" })),
+        )
+        .unwrap();
+
+        assert_eq!(merged.overlay_mode, OverlayMode::Always);
+        assert_eq!(merged.profiles, current.profiles);
+        assert_eq!(merged.active_profile, "Emails");
+    }
+
+    #[test]
+    fn v2_bundle_dictionary_updates_the_default_profile_only() {
+        let current = current_with_profiles();
+        let merged = merge_settings(
+            &current,
+            bundle(json!({ "version": 2, "dictionary": ["Kubernetes"] })),
+        )
+        .unwrap();
+
+        assert_eq!(merged.profiles.len(), 2);
+        assert_eq!(merged.profiles[0].name, DEFAULT_PROFILE);
+        assert_eq!(merged.profiles[0].dictionary[0].canonical, "Kubernetes");
+        assert_eq!(merged.profiles[0].record_shortcut, "ctrl+space");
+        assert_eq!(merged.profiles[1], current.profiles[1]);
+        assert_eq!(merged.active_profile, "Emails");
+    }
+
+    #[test]
+    fn v2_bundle_naming_profiles_is_refused() {
+        let error = merge_settings(
+            &current_with_profiles(),
+            bundle(json!({ "version": 2, "profiles": [] })),
+        )
+        .unwrap_err();
+
+        assert_eq!(error.fields[0].field, "version");
+    }
 }
@@ -1,8 +1,10 @@
 use crate::audio::write_transcription;
 use crate::audio::{record_audio, stop_recording};
-use crate::history::get_last_transcription;
+use crate::history::{get_last_transcription, HistorySource};
+use crate::profiles::{switch_to_next_profile, OutputMethod};
 use crate::shortcuts::{
-    keys_to_string, LastTranscriptShortcutKeys, RecordShortcutKeys, TranscriptionSuspended,
+    keys_to_string, LastTranscriptShortcutKeys, ProfileShortcutKeys, RecordShortcutKeys,
+    TranscriptionSuspended,
 };
 use parking_lot::RwLock;
 use rdev::{listen, Event, EventType, Key};
@@ -111,6 +113,7 @@ pub fn init_shortcuts(app: AppHandle) {
         let app_handle = app.clone();
         let mut is_recording = false;
         let mut last_transcript_pressed = false;
+        let mut profile_pressed = false;
 
         loop {
             if app_handle.state::<TranscriptionSuspended>().get() {
@@ -121,6 +124,7 @@ pub fn init_shortcuts(app: AppHandle) {
             let record_required_keys = app_handle.state::<RecordShortcutKeys>().get();
             let last_transcript_required_keys =
                 app_handle.state::<LastTranscriptShortcutKeys>().get();
+            let profile_required_keys = app_handle.state::<ProfileShortcutKeys>().get();
 
             if record_required_keys.is_empty() {
                 std::thread::sleep(Duration::from_millis(32));
@@ -133,21 +137,25 @@ pub fn init_shortcuts(app: AppHandle) {
                 && last_transcript_required_keys
                     .iter()
                     .all(|k| pressed.contains(k));
+            let all_profile_keys_down = !profile_required_keys.is_empty()
+                && profile_required_keys.iter().all(|k| pressed.contains(k));
 
             if !is_recording && all_record_keys_down {
-                record_audio(&app_handle);
+                if let Err(e) = record_audio(&app_handle) {
+                    eprintln!("Failed to start recording: {}", e);
+                }
                 is_recording = true;
                 let _ = app_handle.emit("shortcut:start", keys_to_string(&record_required_keys));
             }
             if is_recording && !all_record_keys_down {
-                let _ = stop_recording(&app_handle);
+                let _ = stop_recording(&app_handle, HistorySource::Shortcut);
                 is_recording = false;
                 let _ = app_handle.emit("shortcut:stop", keys_to_string(&record_required_keys));
             }
 
             if !last_transcript_pressed && all_last_transcript_keys_down {
                 if let Ok(last_transcript) = get_last_transcription(&app_handle) {
-                    let _ = write_transcription(&app_handle, &last_transcript);
+                    let _ = write_transcription(&app_handle, &last_transcript, OutputMethod::Paste);
                 }
                 last_transcript_pressed = true;
             }
@@ -155,6 +163,16 @@ pub fn init_shortcuts(app: AppHandle) {
                 last_transcript_pressed = false;
             }
 
+            if !profile_pressed && all_profile_keys_down {
+                if let Err(e) = switch_to_next_profile(&app_handle) {
+                    eprintln!("Failed to switch profile: {}", e);
+                }
+                profile_pressed = true;
+            }
+            if profile_pressed && !all_profile_keys_down {
+                profile_pressed = false;
+            }
+
             std::thread::sleep(Duration::from_millis(32));
         }
     });
@@ -31,6 +31,20 @@ impl LastTranscriptShortcutKeys {
     }
 }
 
+pub struct ProfileShortcutKeys(pub Arc<Mutex<Vec<i32>>>);
+
+impl ProfileShortcutKeys {
+    pub fn new(keys: Vec<i32>) -> Self {
+        Self(Arc::new(Mutex::new(keys)))
+    }
+    pub fn get(&self) -> Vec<i32> {
+        self.0.lock().unwrap().clone()
+    }
+    pub fn set(&self, keys: Vec<i32>) {
+        *self.0.lock().unwrap() = keys;
+    }
+}
+
 fn key_name_to_vk(name: &str) -> Option<i32> {
     match name.trim().to_lowercase().as_str() {
         "win" | "meta" | "super" => Some(0x5B),
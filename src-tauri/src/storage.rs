@@ -0,0 +1,84 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// A data file that could not be read and was replaced.
+#[derive(Clone, Debug, Serialize)]
+pub struct Recovery {
+    /// Name of the damaged file, e.g. `settings.json`
+    pub file: String,
+    /// Where the damaged file was moved to, for manual inspection
+    pub quarantined: Option<String>,
+    /// True when the backup was restored, false when starting over from scratch
+    pub restored_from_backup: bool,
+}
+
+// Recoveries usually happen at startup, before the UI listens for events
+static PENDING_RECOVERIES: Lazy<Mutex<Vec<Recovery>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// `path` with `suffix` appended to its file name.
+pub fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Rolling backup of `path`, refreshed before each write.
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+/// Write to a temporary file and rename it over `path`, so a crash never leaves a truncated file.
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let temp_path = with_suffix(path, ".tmp");
+    {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+    }
+    fs::rename(&temp_path, path)?;
+
+    // Make the rename itself durable
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Move a damaged file aside, returns where it went.
+pub fn quarantine(path: &Path) -> io::Result<PathBuf> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let target = with_suffix(path, &format!(".corrupt-{}", timestamp));
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// Record a recovery and tell the UI about it.
+pub fn report_recovery(app: &AppHandle, recovery: Recovery) {
+    eprintln!(
+        "Recovered {}: {}, damaged file moved to {}",
+        recovery.file,
+        if recovery.restored_from_backup {
+            "restored from backup"
+        } else {
+            "started from scratch"
+        },
+        recovery.quarantined.as_deref().unwrap_or("nowhere")
+    );
+    PENDING_RECOVERIES.lock().push(recovery.clone());
+    let _ = app.emit("storage-recovered", recovery);
+}
+
+/// Recoveries not yet shown to the user.
+pub fn take_recoveries() -> Vec<Recovery> {
+    std::mem::take(&mut *PENDING_RECOVERIES.lock())
+}
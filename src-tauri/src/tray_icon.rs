@@ -1,13 +1,75 @@
-use tauri::menu::{Menu, MenuItem};
+use crate::profiles;
+use crate::settings;
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::{TrayIconBuilder, TrayIconEvent};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Listener, Manager, Wry};
 
-pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+const TRAY_ID: &str = "main";
+const PROFILE_PREFIX: &str = "profile:";
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
     let show_i = MenuItem::with_id(app, "show", "Open Murmure", true, None::<&str>)?;
     let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&show_i, &quit_i])?;
 
-    let builder = TrayIconBuilder::new()
+    let s = settings::load_settings(app);
+    let profile_items = s
+        .profiles
+        .iter()
+        .map(|profile| {
+            CheckMenuItem::with_id(
+                app,
+                format!("{}{}", PROFILE_PREFIX, profile.name),
+                &profile.name,
+                true,
+                profile.name == s.active_profile,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let profile_refs: Vec<&dyn IsMenuItem<Wry>> = profile_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let profiles_i = Submenu::with_items(app, "Profile", true, &profile_refs)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+
+    Menu::with_items(app, &[&show_i, &profiles_i, &separator, &quit_i])
+}
+
+/// Rebuild the menu when profiles are added, renamed or switched.
+fn refresh_menu_on_profile_change(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any("settings-changed", move |event| {
+        let changed = serde_json::from_str::<serde_json::Value>(event.payload())
+            .ok()
+            .and_then(|payload| payload.get("changes").cloned())
+            .and_then(|changes| changes.as_array().cloned())
+            .unwrap_or_default()
+            .iter()
+            .any(|change| {
+                matches!(
+                    change.get("field").and_then(|field| field.as_str()),
+                    Some("profiles" | "active_profile")
+                )
+            });
+        if !changed {
+            return;
+        }
+        if let Some(tray) = handle.tray_by_id(TRAY_ID) {
+            match build_menu(&handle) {
+                Ok(menu) => {
+                    let _ = tray.set_menu(Some(menu));
+                }
+                Err(e) => eprintln!("Failed to rebuild tray menu: {}", e),
+            }
+        }
+    });
+}
+
+pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let menu = build_menu(app)?;
+
+    let builder = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .on_menu_event(|app, event| match event.id.as_ref() {
             "show" => {
@@ -19,6 +81,11 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
             "quit" => {
                 app.exit(0);
             }
+            id if id.starts_with(PROFILE_PREFIX) => {
+                if let Err(e) = profiles::switch_profile(app, &id[PROFILE_PREFIX.len()..]) {
+                    eprintln!("Failed to switch profile: {}", e);
+                }
+            }
             _ => {}
         })
         .on_tray_icon_event(|tray, event| {
@@ -44,5 +111,7 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
         .icon(app.default_window_icon().unwrap().clone())
         .build(app)?;
 
+    refresh_menu_on_profile_change(app);
+
     Ok(())
 }
@@ -0,0 +1,343 @@
+use crate::settings;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_LOG_ENTRIES: usize = 100;
+
+pub const DEFAULT_BODY_TEMPLATE: &str = r#"{"text": "{{text}}", "timestamp": {{timestamp}}, "duration": {{duration}}, "history_id": {{history_id}}}"#;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub name: String,
+    pub enabled: bool,
+    pub url: String,
+    pub method: String, // "POST" | "PUT" | "PATCH" | "GET"
+    /// Header values may use the same placeholders as the body
+    pub headers: HashMap<String, String>,
+    /// JSON body with `{{text}}`, `{{timestamp}}`, `{{duration}}` and `{{history_id}}` placeholders
+    pub body_template: String,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            url: String::new(),
+            method: "POST".to_string(),
+            headers: HashMap::new(),
+            body_template: DEFAULT_BODY_TEMPLATE.to_string(),
+        }
+    }
+}
+
+/// What a webhook knows about the dictation that triggered it.
+#[derive(Clone, Debug)]
+pub struct WebhookPayload {
+    pub text: String,
+    pub timestamp: i64,
+    /// Recording duration in seconds
+    pub duration: f64,
+    pub history_id: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WebhookDelivery {
+    pub webhook: String,
+    pub url: String,
+    pub timestamp: i64,
+    pub attempts: u32,
+    pub success: bool,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// Most recent webhook deliveries, newest first.
+pub struct WebhookLog(Mutex<VecDeque<WebhookDelivery>>);
+
+impl WebhookLog {
+    pub fn new() -> Self {
+        Self(Mutex::new(VecDeque::new()))
+    }
+
+    pub fn get(&self) -> Vec<WebhookDelivery> {
+        self.0.lock().iter().cloned().collect()
+    }
+
+    pub fn push(&self, delivery: WebhookDelivery) {
+        let mut log = self.0.lock();
+        log.push_front(delivery);
+        log.truncate(MAX_LOG_ENTRIES);
+    }
+}
+
+impl Default for WebhookLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Escape a value so it can be placed between quotes in a JSON template.
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+pub fn render_body(template: &str, payload: &WebhookPayload) -> String {
+    let template = if template.trim().is_empty() {
+        DEFAULT_BODY_TEMPLATE
+    } else {
+        template
+    };
+    template
+        .replace("{{text}}", &json_escape(&payload.text))
+        .replace("{{timestamp}}", &payload.timestamp.to_string())
+        .replace("{{duration}}", &format!("{:.2}", payload.duration))
+        .replace(
+            "{{history_id}}",
+            &payload
+                .history_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "null".to_string()),
+        )
+}
+
+pub fn render_header(template: &str, payload: &WebhookPayload) -> String {
+    template
+        .replace("{{text}}", &payload.text.replace(['\r', '\n'], " "))
+        .replace("{{timestamp}}", &payload.timestamp.to_string())
+        .replace("{{duration}}", &format!("{:.2}", payload.duration))
+        .replace(
+            "{{history_id}}",
+            &payload
+                .history_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+        )
+}
+
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Send one webhook, retrying with exponential backoff on network errors and 5xx/429 answers.
+pub async fn deliver(
+    client: &reqwest::Client,
+    webhook: &WebhookConfig,
+    payload: &WebhookPayload,
+    initial_backoff: Duration,
+) -> WebhookDelivery {
+    let mut delivery = WebhookDelivery {
+        webhook: webhook.name.clone(),
+        url: webhook.url.clone(),
+        timestamp: now_secs(),
+        attempts: 0,
+        success: false,
+        status: None,
+        error: None,
+    };
+
+    let method = match reqwest::Method::from_bytes(webhook.method.trim().to_uppercase().as_bytes())
+    {
+        Ok(method) => method,
+        Err(_) => {
+            delivery.error = Some(format!("Invalid HTTP method '{}'", webhook.method));
+            return delivery;
+        }
+    };
+    let body = render_body(&webhook.body_template, payload);
+    let mut backoff = initial_backoff;
+
+    while delivery.attempts < MAX_ATTEMPTS {
+        if delivery.attempts > 0 {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+        delivery.attempts += 1;
+
+        let mut request = client
+            .request(method.clone(), &webhook.url)
+            .timeout(REQUEST_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        for (name, value) in &webhook.headers {
+            request = request.header(name.as_str(), render_header(value, payload));
+        }
+        if method != reqwest::Method::GET {
+            request = request.body(body.clone());
+        }
+
+        match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                delivery.status = Some(status.as_u16());
+                if status.is_success() {
+                    delivery.success = true;
+                    delivery.error = None;
+                    return delivery;
+                }
+                delivery.error = Some(format!("Server answered {}", status));
+                if !is_retryable(status) {
+                    return delivery;
+                }
+            }
+            Err(e) => {
+                delivery.status = None;
+                delivery.error = Some(e.to_string());
+            }
+        }
+    }
+
+    delivery
+}
+
+/// Fire every enabled webhook in the background after a dictation.
+pub fn dispatch(app: &AppHandle, payload: WebhookPayload) {
+    let webhooks: Vec<WebhookConfig> = settings::load_settings(app)
+        .webhooks
+        .into_iter()
+        .filter(|webhook| webhook.enabled && !webhook.url.trim().is_empty())
+        .collect();
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                eprintln!("Failed to create async runtime for webhooks: {}", e);
+                return;
+            }
+        };
+        let client = reqwest::Client::new();
+
+        runtime.block_on(async {
+            let deliveries = webhooks
+                .iter()
+                .map(|webhook| deliver(&client, webhook, &payload, INITIAL_BACKOFF));
+            for delivery in futures_util::future::join_all(deliveries).await {
+                if !delivery.success {
+                    eprintln!(
+                        "Webhook '{}' failed after {} attempt(s): {}",
+                        delivery.webhook,
+                        delivery.attempts,
+                        delivery.error.as_deref().unwrap_or("unknown error")
+                    );
+                }
+                app.state::<WebhookLog>().push(delivery);
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::StatusCode, routing::post, Router};
+    use std::sync::Arc;
+
+    fn payload() -> WebhookPayload {
+        WebhookPayload {
+            text: "Say \"hello\"\nplease".to_string(),
+            timestamp: 1761400000,
+            duration: 2.5,
+            history_id: Some(7),
+        }
+    }
+
+    #[test]
+    fn body_template_escapes_text() {
+        let body = render_body("", &payload());
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["text"], "Say \"hello\"\nplease");
+        assert_eq!(value["timestamp"], 1761400000);
+        assert_eq!(value["duration"], 2.5);
+        assert_eq!(value["history_id"], 7);
+    }
+
+    #[tokio::test]
+    async fn retries_until_the_server_accepts() {
+        // Fail the first two calls, then accept
+        let received = Arc::new(Mutex::new(Vec::<String>::new()));
+        let router = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(received): State<Arc<Mutex<Vec<String>>>>, body: String| async move {
+                        let mut received = received.lock();
+                        received.push(body);
+                        if received.len() < 3 {
+                            StatusCode::SERVICE_UNAVAILABLE
+                        } else {
+                            StatusCode::OK
+                        }
+                    },
+                ),
+            )
+            .with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let webhook = WebhookConfig {
+            name: "ticketing".to_string(),
+            url: format!("http://{}/hook", addr),
+            headers: HashMap::from([("X-Dictation".to_string(), "{{history_id}}".to_string())]),
+            ..Default::default()
+        };
+        let delivery = deliver(
+            &reqwest::Client::new(),
+            &webhook,
+            &payload(),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert!(delivery.success);
+        assert_eq!(delivery.attempts, 3);
+        assert_eq!(delivery.status, Some(200));
+        assert_eq!(received.lock().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let router = Router::new().route("/hook", post(|| async { StatusCode::BAD_REQUEST }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let webhook = WebhookConfig {
+            url: format!("http://{}/hook", addr),
+            ..Default::default()
+        };
+        let delivery = deliver(
+            &reqwest::Client::new(),
+            &webhook,
+            &payload(),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert!(!delivery.success);
+        assert_eq!(delivery.attempts, 1);
+        assert_eq!(delivery.status, Some(400));
+    }
+}
@@ -0,0 +1,35 @@
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+
+export interface SettingsChange {
+    field: string;
+    old: unknown;
+    new: unknown;
+}
+
+interface SettingsChanged {
+    source: 'app' | 'file';
+    changes: SettingsChange[];
+}
+
+// Calls onChange when one of the given settings changed, including edits of settings.json
+export const useSettingsChanged = (
+    fields: string[],
+    onChange: (changes: SettingsChange[]) => void
+) => {
+    useEffect(() => {
+        const unlistenPromise = listen<SettingsChanged>(
+            'settings-changed',
+            (event) => {
+                const changes = event.payload.changes.filter((change) =>
+                    fields.includes(change.field)
+                );
+                if (changes.length > 0) onChange(changes);
+            }
+        );
+
+        return () => {
+            unlistenPromise.then((unlisten) => unlisten());
+        };
+    }, []);
+};
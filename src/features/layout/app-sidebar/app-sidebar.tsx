@@ -5,6 +5,7 @@ import {
     ChevronRight,
     Keyboard,
     BookText,
+    Layers,
     Power,
 } from 'lucide-react';
 import { Link } from '@tanstack/react-router';
@@ -33,6 +34,7 @@ const settingsSubItems = [
         url: '/settings/custom-dictionary',
         icon: BookText,
     },
+    { name: 'Profiles', url: '/settings/profiles', icon: Layers },
     { name: 'System', url: '/settings/system', icon: Power },
 ];
 
@@ -0,0 +1,48 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import { toast } from 'sonner';
+
+interface Recovery {
+    file: string;
+    quarantined: string | null;
+    restored_from_backup: boolean;
+}
+
+export const useStorageRecovery = () => {
+    useEffect(() => {
+        showRecoveries();
+
+        // The event only signals, the pending list is the source of truth
+        const unlistenPromise = listen('storage-recovered', () => {
+            showRecoveries();
+        });
+
+        return () => {
+            unlistenPromise.then((unlisten) => unlisten());
+        };
+    }, []);
+
+    const showRecoveries = async () => {
+        try {
+            const recoveries = await invoke<Recovery[]>(
+                'take_storage_recoveries'
+            );
+            recoveries.forEach((recovery) => {
+                toast.warning(
+                    recovery.restored_from_backup
+                        ? `${recovery.file} was damaged and has been restored from its backup`
+                        : `${recovery.file} was damaged and has been reset`,
+                    {
+                        description: recovery.quarantined
+                            ? `The damaged file was kept as ${recovery.quarantined}`
+                            : undefined,
+                        duration: 10000,
+                    }
+                );
+            });
+        } catch (e) {
+            console.error('Failed to load storage recoveries:', e);
+        }
+    };
+};
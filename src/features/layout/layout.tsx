@@ -2,8 +2,11 @@ import { Outlet } from '@tanstack/react-router';
 import { SidebarProvider, SidebarInset } from '../../components/sidebar';
 import { AppSidebar } from './app-sidebar/app-sidebar';
 import { Toaster } from 'sonner';
+import { useStorageRecovery } from './hooks/use-storage-recovery';
 
 export const Layout = () => {
+    useStorageRecovery();
+
     return (
         <SidebarProvider defaultOpen={true} className="bg-zinc-900 dark">
             <AppSidebar />
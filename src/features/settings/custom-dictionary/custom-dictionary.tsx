@@ -1,42 +1,25 @@
-import { useEffect, useState } from 'react';
+import { useState } from 'react';
 import { Input } from '../../../components/input';
 import { Button } from '../../../components/button';
 import { BookText } from 'lucide-react';
-import { invoke } from '@tauri-apps/api/core';
-import { toast } from 'sonner';
 import { Page } from '@/components/page';
 import { Typography } from '@/components/typography';
+import { useDictionaryState } from './hooks/use-dictionary-state';
+import { EntryEditor } from './entry-editor/entry-editor';
 
 export const CustomDictionary = () => {
-    const [customWords, setCustomWords] = useState<string[]>([]);
+    const { entries, addEntry, updateEntry, removeEntry } =
+        useDictionaryState();
     const [newWord, setNewWord] = useState('');
-
-    useEffect(() => {
-        invoke<string[]>('get_dictionary').then((words) => {
-            setCustomWords(words ?? []);
-        });
-    }, []);
-
-    const persist = (next: string[]) => {
-        setCustomWords(next);
-        invoke('set_dictionary', { dictionary: next })
-            .then(() => toast.success('Dictionary updated'))
-            .catch(() => toast.error('Failed to update dictionary'));
-    };
+    const [editing, setEditing] = useState<string | null>(null);
 
     const handleAddWord = () => {
         const trimmed = newWord.trim();
         if (!trimmed) return;
-        if (customWords.includes(trimmed)) return;
-        persist([...customWords, trimmed]);
+        addEntry(trimmed);
         setNewWord('');
     };
 
-    const handleRemoveWord = (word: string) => {
-        const next = customWords.filter((w) => w !== word);
-        persist(next);
-    };
-
     const handleKeyDown = (e: React.KeyboardEvent) => {
         if (e.key === 'Enter') {
             e.preventDefault();
@@ -44,14 +27,17 @@ export const CustomDictionary = () => {
         }
     };
 
+    const editedEntry = entries.find((e) => e.canonical === editing) ?? null;
+
     return (
         <main className="space-y-8">
             <Page.Header>
                 <Typography.MainTitle>Custom Dictionary</Typography.MainTitle>
                 <Typography.Paragraph className="text-zinc-400">
                     Personalize your Murmure experience by adding technical
-                    terms, names, or specialized vocabulary to the dictionary
-                    (optimized for both English and French).
+                    terms, names, or specialized vocabulary to the dictionary.
+                    Click a word to set its languages, aliases and how closely
+                    it must match.
                 </Typography.Paragraph>
             </Page.Header>
 
@@ -79,21 +65,34 @@ export const CustomDictionary = () => {
                         Add
                     </Button>
                 </div>
-                {customWords.length > 0 && (
+                {entries.length > 0 && (
                     <div className="flex flex-wrap gap-2 mt-4">
-                        {customWords.map((word) => (
+                        {entries.map((entry) => (
                             <button
-                                key={word}
-                                onClick={() => handleRemoveWord(word)}
+                                key={entry.canonical}
+                                onClick={() => setEditing(entry.canonical)}
                                 className="inline-flex items-center gap-1.5 px-3 py-1.5 text-xs bg-zinc-800 hover:bg-zinc-700 text-zinc-300 rounded-md border border-zinc-700 transition-colors"
                             >
-                                <span>{word}</span>
-                                <span className="text-zinc-500">×</span>
+                                <span>{entry.canonical}</span>
+                                {entry.languages.length > 0 && (
+                                    <span className="text-zinc-500">
+                                        {entry.languages
+                                            .map((l) => l.slice(0, 2))
+                                            .join(' ')}
+                                    </span>
+                                )}
                             </button>
                         ))}
                     </div>
                 )}
             </div>
+
+            <EntryEditor
+                entry={editedEntry}
+                onSave={(entry) => updateEntry(entry.canonical, entry)}
+                onRemove={() => editing && removeEntry(editing)}
+                onClose={() => setEditing(null)}
+            />
         </main>
     );
 };
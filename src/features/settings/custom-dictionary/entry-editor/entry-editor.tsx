@@ -0,0 +1,145 @@
+import { useEffect, useState } from 'react';
+import { Input } from '@/components/input';
+import { Button } from '@/components/button';
+import { Switch } from '@/components/switch';
+import { Typography } from '@/components/typography';
+import {
+    Dialog,
+    DialogContent,
+    DialogDescription,
+    DialogFooter,
+    DialogHeader,
+    DialogTitle,
+} from '@/components/dialog';
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from '@/components/select';
+import {
+    DictionaryEntry,
+    MatchStrictness,
+} from '../hooks/use-dictionary-state';
+
+const splitList = (value: string) =>
+    value
+        .split(',')
+        .map((item) => item.trim())
+        .filter(Boolean);
+
+export const EntryEditor = ({
+    entry,
+    onSave,
+    onRemove,
+    onClose,
+}: {
+    entry: DictionaryEntry | null;
+    onSave: (entry: DictionaryEntry) => void;
+    onRemove: () => void;
+    onClose: () => void;
+}) => {
+    const [aliases, setAliases] = useState('');
+    const [languages, setLanguages] = useState('');
+    const [caseSensitive, setCaseSensitive] = useState(false);
+    const [strictness, setStrictness] = useState<MatchStrictness>('loose');
+
+    useEffect(() => {
+        if (!entry) return;
+        setAliases(entry.aliases.join(', '));
+        setLanguages(entry.languages.join(', '));
+        setCaseSensitive(entry.case_sensitive);
+        setStrictness(entry.strictness);
+    }, [entry]);
+
+    if (!entry) return null;
+
+    const handleSave = () => {
+        onSave({
+            ...entry,
+            aliases: splitList(aliases),
+            languages: splitList(languages).map((l) => l.toLowerCase()),
+            case_sensitive: caseSensitive,
+            strictness,
+        });
+        onClose();
+    };
+
+    return (
+        <Dialog open onOpenChange={(open) => !open && onClose()}>
+            <DialogContent>
+                <DialogHeader>
+                    <DialogTitle>{entry.canonical}</DialogTitle>
+                    <DialogDescription>
+                        Choose how this word is recognized.
+                    </DialogDescription>
+                </DialogHeader>
+                <div className="space-y-4">
+                    <div className="space-y-1">
+                        <Typography.Title>Aliases</Typography.Title>
+                        <Typography.Paragraph>
+                            Other ways the word comes out, separated by commas.
+                        </Typography.Paragraph>
+                        <Input
+                            value={aliases}
+                            onChange={(e) => setAliases(e.target.value)}
+                        />
+                    </div>
+                    <div className="space-y-1">
+                        <Typography.Title>Languages</Typography.Title>
+                        <Typography.Paragraph>
+                            e.g. german, spanish. Leave empty to guess from the
+                            spelling.
+                        </Typography.Paragraph>
+                        <Input
+                            value={languages}
+                            onChange={(e) => setLanguages(e.target.value)}
+                        />
+                    </div>
+                    <div className="flex items-center justify-between">
+                        <Typography.Title>Matching</Typography.Title>
+                        <Select
+                            value={strictness}
+                            onValueChange={(s) =>
+                                setStrictness(s as MatchStrictness)
+                            }
+                        >
+                            <SelectTrigger className="w-[150px]">
+                                <SelectValue />
+                            </SelectTrigger>
+                            <SelectContent>
+                                <SelectItem value="loose">Sounds alike</SelectItem>
+                                <SelectItem value="strict">
+                                    Sounds the same
+                                </SelectItem>
+                                <SelectItem value="exact">
+                                    Spelled the same
+                                </SelectItem>
+                            </SelectContent>
+                        </Select>
+                    </div>
+                    <div className="flex items-center justify-between">
+                        <Typography.Title>Keep exact casing</Typography.Title>
+                        <Switch
+                            checked={caseSensitive}
+                            onCheckedChange={setCaseSensitive}
+                        />
+                    </div>
+                </div>
+                <DialogFooter>
+                    <Button
+                        variant="outline"
+                        onClick={() => {
+                            onRemove();
+                            onClose();
+                        }}
+                    >
+                        Remove
+                    </Button>
+                    <Button onClick={handleSave}>Save</Button>
+                </DialogFooter>
+            </DialogContent>
+        </Dialog>
+    );
+};
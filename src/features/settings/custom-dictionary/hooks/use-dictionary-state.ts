@@ -0,0 +1,64 @@
+import { invoke } from '@tauri-apps/api/core';
+import { useState, useEffect } from 'react';
+import { toast } from 'sonner';
+import { useSettingsChanged } from '@/components/hooks/use-settings-changed';
+
+export type MatchStrictness = 'loose' | 'strict' | 'exact';
+
+export interface DictionaryEntry {
+    canonical: string;
+    aliases: string[];
+    languages: string[];
+    case_sensitive: boolean;
+    strictness: MatchStrictness;
+}
+
+export const newEntry = (canonical: string): DictionaryEntry => ({
+    canonical,
+    aliases: [],
+    languages: ['french', 'english'],
+    case_sensitive: false,
+    strictness: 'loose',
+});
+
+export const useDictionaryState = () => {
+    const [entries, setEntries] = useState<DictionaryEntry[]>([]);
+
+    const loadDictionary = () => {
+        invoke<DictionaryEntry[]>('get_dictionary').then((dictionary) => {
+            setEntries(dictionary ?? []);
+        });
+    };
+
+    useEffect(() => {
+        loadDictionary();
+    }, []);
+
+    useSettingsChanged(['profiles', 'active_profile'], loadDictionary);
+
+    const persist = (next: DictionaryEntry[]) => {
+        const previous = entries;
+        setEntries(next);
+        invoke('set_dictionary', { dictionary: next })
+            .then(() => toast.success('Dictionary updated'))
+            .catch((error) => {
+                setEntries(previous);
+                toast.error(
+                    (error as { message?: string })?.message ??
+                        'Failed to update dictionary'
+                );
+            });
+    };
+
+    return {
+        entries,
+        addEntry: (canonical: string) => {
+            if (entries.some((e) => e.canonical === canonical)) return;
+            persist([...entries, newEntry(canonical)]);
+        },
+        updateEntry: (canonical: string, entry: DictionaryEntry) =>
+            persist(entries.map((e) => (e.canonical === canonical ? entry : e))),
+        removeEntry: (canonical: string) =>
+            persist(entries.filter((e) => e.canonical !== canonical)),
+    };
+};
@@ -0,0 +1,107 @@
+import { invoke } from '@tauri-apps/api/core';
+import { useState, useEffect } from 'react';
+import { toast } from 'sonner';
+import { useSettingsChanged } from '@/components/hooks/use-settings-changed';
+import { DictionaryEntry } from '../../custom-dictionary/hooks/use-dictionary-state';
+
+export type OutputMethod = 'paste' | 'clipboard' | 'none';
+
+export interface PostProcessingRule {
+    find: string;
+    replace: string;
+    regex: boolean;
+}
+
+export interface Profile {
+    name: string;
+    dictionary: DictionaryEntry[];
+    record_shortcut: string;
+    last_transcript_shortcut: string;
+    post_processing: PostProcessingRule[];
+    output_method: OutputMethod;
+}
+
+interface ProfileList {
+    profiles: Profile[];
+    active_profile: string;
+}
+
+const errorMessage = (error: unknown) =>
+    (error as { message?: string })?.message ?? String(error);
+
+export const useProfilesState = () => {
+    const [profiles, setProfiles] = useState<Profile[]>([]);
+    const [activeProfile, setActiveProfile] = useState('');
+    const [profileShortcut, setProfileShortcut] = useState('');
+
+    const apply = (list: ProfileList) => {
+        setProfiles(list.profiles);
+        setActiveProfile(list.active_profile);
+    };
+
+    const loadProfiles = async () => {
+        try {
+            apply(await invoke<ProfileList>('get_profiles'));
+            setProfileShortcut(await invoke<string>('get_profile_shortcut'));
+        } catch (error) {
+            console.error('Failed to load profiles:', error);
+        }
+    };
+
+    useEffect(() => {
+        loadProfiles();
+    }, []);
+
+    useSettingsChanged(
+        ['profiles', 'active_profile', 'profile_shortcut'],
+        loadProfiles
+    );
+
+    const run = async (command: string, args: Record<string, unknown>) => {
+        try {
+            apply(await invoke<ProfileList>(command, args));
+        } catch (error) {
+            toast.error(errorMessage(error));
+        }
+    };
+
+    const active = profiles.find((p) => p.name === activeProfile);
+
+    return {
+        profiles,
+        activeProfile,
+        outputMethod: active?.output_method ?? 'paste',
+        rules: active?.post_processing ?? [],
+        profileShortcut,
+        switchProfile: (name: string) => run('switch_profile', { name }),
+        createProfile: (name: string) => run('create_profile', { name }),
+        renameProfile: (name: string, newName: string) =>
+            run('rename_profile', { name, newName }),
+        deleteProfile: (name: string) => run('delete_profile', { name }),
+        setOutputMethod: async (method: OutputMethod) => {
+            try {
+                await invoke('set_output_method', { method });
+                await loadProfiles();
+            } catch (error) {
+                toast.error(errorMessage(error));
+            }
+        },
+        setRules: async (rules: PostProcessingRule[]) => {
+            try {
+                await invoke('set_post_processing_rules', { rules });
+                await loadProfiles();
+            } catch (error) {
+                toast.error(errorMessage(error));
+            }
+        },
+        setProfileShortcut: async (binding: string) => {
+            try {
+                setProfileShortcut(
+                    await invoke<string>('set_profile_shortcut', { binding })
+                );
+            } catch {
+                toast('Failed to save shortcut');
+            }
+        },
+    };
+};
@@ -0,0 +1,282 @@
+import { useState } from 'react';
+import { Layers, Send, Trash2, WandSparkles } from 'lucide-react';
+import { Page } from '@/components/page';
+import { Typography } from '@/components/typography';
+import { SettingsUI } from '@/components/settings-ui';
+import { Input } from '@/components/input';
+import { Button } from '@/components/button';
+import { Switch } from '@/components/switch';
+import { RenderKeys } from '@/components/render-keys';
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from '@/components/select';
+import { ShortcutButton } from '../shortcuts/shortcut-button/shortcut-button';
+import { OutputMethod, useProfilesState } from './hooks/use-profiles-state';
+
+export const Profiles = () => {
+    const {
+        profiles,
+        activeProfile,
+        outputMethod,
+        rules,
+        profileShortcut,
+        switchProfile,
+        createProfile,
+        renameProfile,
+        deleteProfile,
+        setOutputMethod,
+        setRules,
+        setProfileShortcut,
+    } = useProfilesState();
+    const [newName, setNewName] = useState('');
+    const [rename, setRename] = useState('');
+    const [find, setFind] = useState('');
+    const [replace, setReplace] = useState('');
+
+    const handleCreate = () => {
+        if (!newName.trim()) return;
+        createProfile(newName.trim());
+        setNewName('');
+    };
+
+    const handleRename = () => {
+        if (!rename.trim()) return;
+        renameProfile(activeProfile, rename.trim());
+        setRename('');
+    };
+
+    const handleAddRule = () => {
+        if (!find) return;
+        setRules([...rules, { find, replace, regex: false }]);
+        setFind('');
+        setReplace('');
+    };
+
+    return (
+        <main>
+            <div className="space-y-8">
+                <Page.Header>
+                    <Typography.MainTitle>Profiles</Typography.MainTitle>
+                    <Typography.Paragraph className="text-zinc-400">
+                        Keep a dictionary, shortcuts and output settings per
+                        context, e.g. code comments versus emails, and switch
+                        between them from here, the tray or a shortcut.
+                    </Typography.Paragraph>
+                </Page.Header>
+
+                <SettingsUI.Container>
+                    <SettingsUI.Item>
+                        <SettingsUI.Description>
+                            <Typography.Title className="flex items-center gap-2">
+                                <Layers className="w-4 h-4 text-zinc-400" />
+                                Active profile
+                            </Typography.Title>
+                            <Typography.Paragraph>
+                                The dictionary and shortcuts pages edit this
+                                profile.
+                            </Typography.Paragraph>
+                        </SettingsUI.Description>
+                        <div className="flex gap-2">
+                            <Select
+                                value={activeProfile}
+                                onValueChange={switchProfile}
+                            >
+                                <SelectTrigger className="w-[150px]">
+                                    <SelectValue placeholder="Select a profile" />
+                                </SelectTrigger>
+                                <SelectContent>
+                                    {profiles.map((p) => (
+                                        <SelectItem key={p.name} value={p.name}>
+                                            {p.name}
+                                        </SelectItem>
+                                    ))}
+                                </SelectContent>
+                            </Select>
+                            <Button
+                                variant="outline"
+                                onClick={() => deleteProfile(activeProfile)}
+                                disabled={profiles.length <= 1}
+                            >
+                                <Trash2 className="w-4 h-4" />
+                            </Button>
+                        </div>
+                    </SettingsUI.Item>
+                    <SettingsUI.Separator />
+                    <SettingsUI.Item>
+                        <SettingsUI.Description>
+                            <Typography.Title>New profile</Typography.Title>
+                            <Typography.Paragraph>
+                                Starts as a copy of the active profile.
+                            </Typography.Paragraph>
+                        </SettingsUI.Description>
+                        <div className="flex gap-2">
+                            <Input
+                                type="text"
+                                value={newName}
+                                onChange={(e) => setNewName(e.target.value)}
+                                placeholder="Name"
+                            />
+                            <Button
+                                variant="outline"
+                                onClick={handleCreate}
+                                disabled={!newName.trim()}
+                            >
+                                Create
+                            </Button>
+                        </div>
+                    </SettingsUI.Item>
+                    <SettingsUI.Separator />
+                    <SettingsUI.Item>
+                        <SettingsUI.Description>
+                            <Typography.Title>Rename</Typography.Title>
+                            <Typography.Paragraph>
+                                Give the active profile a new name.
+                            </Typography.Paragraph>
+                        </SettingsUI.Description>
+                        <div className="flex gap-2">
+                            <Input
+                                type="text"
+                                value={rename}
+                                onChange={(e) => setRename(e.target.value)}
+                                placeholder={activeProfile}
+                            />
+                            <Button
+                                variant="outline"
+                                onClick={handleRename}
+                                disabled={!rename.trim()}
+                            >
+                                Rename
+                            </Button>
+                        </div>
+                    </SettingsUI.Item>
+                    <SettingsUI.Separator />
+                    <SettingsUI.Item>
+                        <SettingsUI.Description>
+                            <Typography.Title className="flex items-center gap-2">
+                                <Send className="w-4 h-4 text-zinc-400" />
+                                Output
+                            </Typography.Title>
+                            <Typography.Paragraph>
+                                What happens to the text once it is
+                                transcribed.
+                            </Typography.Paragraph>
+                        </SettingsUI.Description>
+                        <Select
+                            value={outputMethod}
+                            onValueChange={(m) =>
+                                setOutputMethod(m as OutputMethod)
+                            }
+                        >
+                            <SelectTrigger className="w-[150px]">
+                                <SelectValue placeholder="Select an output" />
+                            </SelectTrigger>
+                            <SelectContent>
+                                <SelectItem value="paste">Paste</SelectItem>
+                                <SelectItem value="clipboard">
+                                    Clipboard only
+                                </SelectItem>
+                                <SelectItem value="none">
+                                    History only
+                                </SelectItem>
+                            </SelectContent>
+                        </Select>
+                    </SettingsUI.Item>
+                    <SettingsUI.Separator />
+                    <SettingsUI.Item>
+                        <SettingsUI.Description>
+                            <Typography.Title>Switch profile</Typography.Title>
+                            <Typography.Paragraph>
+                                {profileShortcut ? (
+                                    <>
+                                        Press{' '}
+                                        <RenderKeys
+                                            keyString={profileShortcut}
+                                        />{' '}
+                                        to switch to the next profile.
+                                    </>
+                                ) : (
+                                    'Set a shortcut to switch to the next profile.'
+                                )}
+                            </Typography.Paragraph>
+                        </SettingsUI.Description>
+                        <ShortcutButton
+                            keyName="Switch profile"
+                            shortcut={profileShortcut}
+                            saveShortcut={setProfileShortcut}
+                            resetShortcut={() => setProfileShortcut('')}
+                        />
+                    </SettingsUI.Item>
+                </SettingsUI.Container>
+
+                <div className="space-y-2 w-full">
+                    <Typography.Title className="space-x-2">
+                        <WandSparkles className="w-4 h-4 text-zinc-400 inline-block" />
+                        <span>Post-processing</span>
+                    </Typography.Title>
+                    <Typography.Paragraph>
+                        Replacements applied in order after the dictionary
+                        correction. Regular expressions may use $1 for groups.
+                    </Typography.Paragraph>
+                    <div className="flex items-center gap-2">
+                        <Input
+                            type="text"
+                            value={find}
+                            onChange={(e) => setFind(e.target.value)}
+                            placeholder="Find"
+                        />
+                        <Input
+                            type="text"
+                            value={replace}
+                            onChange={(e) => setReplace(e.target.value)}
+                            placeholder="Replace with"
+                        />
+                        <Button
+                            variant="outline"
+                            onClick={handleAddRule}
+                            disabled={!find}
+                        >
+                            Add
+                        </Button>
+                    </div>
+                    {rules.map((rule, index) => (
+                        <div
+                            key={index}
+                            className="flex items-center gap-2 text-xs text-zinc-300"
+                        >
+                            <code className="flex-1 truncate">{rule.find}</code>
+                            <span className="text-zinc-500">→</span>
+                            <code className="flex-1 truncate">
+                                {rule.replace}
+                            </code>
+                            <span className="text-zinc-500">Regex</span>
+                            <Switch
+                                checked={rule.regex}
+                                onCheckedChange={(regex) =>
+                                    setRules(
+                                        rules.map((r, i) =>
+                                            i === index ? { ...r, regex } : r
+                                        )
+                                    )
+                                }
+                            />
+                            <Button
+                                variant="outline"
+                                onClick={() =>
+                                    setRules(
+                                        rules.filter((_, i) => i !== index)
+                                    )
+                                }
+                            >
+                                <Trash2 className="w-4 h-4" />
+                            </Button>
+                        </div>
+                    ))}
+                </div>
+            </div>
+        </main>
+    );
+};
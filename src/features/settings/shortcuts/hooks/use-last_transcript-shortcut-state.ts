@@ -1,6 +1,7 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useState, useEffect } from 'react';
 import { toast } from 'sonner';
+import { useSettingsChanged } from '@/components/hooks/use-settings-changed';
 
 export const useLastTranscriptShortcutState = () => {
     const [shortcut, setShortcut] = useState('ctrl+shift+space');
@@ -18,6 +19,8 @@ export const useLastTranscriptShortcutState = () => {
         loadShortcut();
     }, []);
 
+    useSettingsChanged(['profiles', 'active_profile'], loadShortcut);
+
     const saveShortcut = async (value: string) => {
         if (value == null) return;
         try {
@@ -1,6 +1,7 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useState, useEffect } from 'react';
 import { toast } from 'sonner';
+import { useSettingsChanged } from '@/components/hooks/use-settings-changed';
 
 export const useRecordShortcutState = () => {
     const [shortcut, setShortcut] = useState('ctrl+space');
@@ -18,6 +19,8 @@ export const useRecordShortcutState = () => {
         loadShortcut();
     }, []);
 
+    useSettingsChanged(['profiles', 'active_profile'], loadShortcut);
+
     const saveShortcut = async (value: string) => {
         if (value == null) return;
         try {
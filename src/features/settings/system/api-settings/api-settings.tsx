@@ -2,12 +2,36 @@ import { NumberInput } from '@/components/number-input';
 import { SettingsUI } from '@/components/settings-ui';
 import { Typography } from '@/components/typography';
 import { useApiState } from './hooks/use-api-state';
-import { FileCode2, Zap } from 'lucide-react';
+import { FileCode2, RefreshCw, Zap } from 'lucide-react';
 import { Switch } from '@/components/switch';
 import { ExternalLink } from '@/components/external-link';
+import { Button } from '@/components/button';
+import { Input } from '@/components/input';
+import { useEffect, useState } from 'react';
 
 export const APISettings = () => {
-    const { apiEnabled, setApiEnabled, apiPort, setApiPort } = useApiState();
+    const {
+        apiEnabled,
+        setApiEnabled,
+        apiPort,
+        setApiPort,
+        apiKey,
+        regenerateApiKey,
+        apiStatus,
+        apiBindAddress,
+        setApiBindAddress,
+        apiAllowExternalBind,
+        setApiAllowExternalBind,
+        apiSocketPath,
+        setApiSocketPath,
+        bindError,
+    } = useApiState();
+    // Edited locally and saved on blur, so a half-typed address isn't rejected
+    const [bindAddressDraft, setBindAddressDraft] = useState(apiBindAddress);
+    const [socketPathDraft, setSocketPathDraft] = useState(apiSocketPath);
+
+    useEffect(() => setBindAddressDraft(apiBindAddress), [apiBindAddress]);
+    useEffect(() => setSocketPathDraft(apiSocketPath), [apiSocketPath]);
 
     return (
         <>
@@ -27,8 +51,15 @@ export const APISettings = () => {
                         </div>
                         <code className="text-xs block border p-2">
                             curl -X POST http://localhost:{apiPort}
-                            /api/transcribe -F "audio=@audio.wav;type=audio/wav"
+                            /api/transcribe -H "Authorization: Bearer
+                            $MURMURE_API_KEY" -F
+                            "audio=@audio.wav;type=audio/wav"
                         </code>
+                        {apiEnabled && apiStatus?.state === 'error' && (
+                            <div className="text-xs text-red-400">
+                                {apiStatus.last_error}
+                            </div>
+                        )}
                         <div className="text-xs flex items-center gap-1">
                             <FileCode2 className="w-4 h-4 text-zinc-400 inline-block" />
                             View{' '}
@@ -58,6 +89,100 @@ export const APISettings = () => {
                             onValueChange={(value) => setApiPort(value ?? 4800)}
                         />
                     </SettingsUI.Item>
+                    <SettingsUI.Separator />
+                    <SettingsUI.Item>
+                        <SettingsUI.Description>
+                            <Typography.Title>Listen Address</Typography.Title>
+                            <Typography.Paragraph>
+                                Interface the HTTP API listens on. Only
+                                127.0.0.1 keeps it private to this machine.
+                            </Typography.Paragraph>
+                        </SettingsUI.Description>
+                        <Input
+                            className="w-48"
+                            value={bindAddressDraft}
+                            disabled={apiSocketPath !== ''}
+                            onChange={(event) =>
+                                setBindAddressDraft(event.target.value)
+                            }
+                            onBlur={() => {
+                                if (bindAddressDraft !== apiBindAddress) {
+                                    setApiBindAddress(bindAddressDraft);
+                                }
+                            }}
+                        />
+                    </SettingsUI.Item>
+                    <SettingsUI.Separator />
+                    <SettingsUI.Item>
+                        <SettingsUI.Description>
+                            <Typography.Title>
+                                Allow Other Machines
+                            </Typography.Title>
+                            <Typography.Paragraph>
+                                Required to listen on an address other
+                                machines on the network can reach.
+                            </Typography.Paragraph>
+                        </SettingsUI.Description>
+                        <Switch
+                            checked={apiAllowExternalBind}
+                            onCheckedChange={setApiAllowExternalBind}
+                        />
+                    </SettingsUI.Item>
+                    <SettingsUI.Separator />
+                    <SettingsUI.Item>
+                        <SettingsUI.Description>
+                            <Typography.Title>Unix Socket</Typography.Title>
+                            <Typography.Paragraph className="space-y-2">
+                                <div>
+                                    Absolute path of a socket to listen on
+                                    instead of the port, only you can
+                                    connect to it. Leave empty to use the
+                                    port.
+                                </div>
+                                {bindError && (
+                                    <div className="text-xs text-red-400">
+                                        {bindError}
+                                    </div>
+                                )}
+                            </Typography.Paragraph>
+                        </SettingsUI.Description>
+                        <Input
+                            className="w-48"
+                            placeholder="/run/user/1000/murmure.sock"
+                            value={socketPathDraft}
+                            onChange={(event) =>
+                                setSocketPathDraft(event.target.value)
+                            }
+                            onBlur={() => {
+                                if (socketPathDraft !== apiSocketPath) {
+                                    setApiSocketPath(socketPathDraft);
+                                }
+                            }}
+                        />
+                    </SettingsUI.Item>
+                    <SettingsUI.Separator />
+                    <SettingsUI.Item>
+                        <SettingsUI.Description>
+                            <Typography.Title>API Key</Typography.Title>
+                            <Typography.Paragraph className="space-y-2">
+                                <div>
+                                    Send it as a bearer token with every
+                                    request.
+                                </div>
+                                <code className="text-xs block border p-2 break-all select-all">
+                                    {apiKey}
+                                </code>
+                            </Typography.Paragraph>
+                        </SettingsUI.Description>
+                        <Button
+                            variant="outline"
+                            size="sm"
+                            onClick={regenerateApiKey}
+                        >
+                            <RefreshCw />
+                            Regenerate
+                        </Button>
+                    </SettingsUI.Item>
                 </>
             )}
         </>
@@ -1,9 +1,38 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { useState, useEffect } from 'react';
+import { useSettingsChanged } from '@/components/hooks/use-settings-changed';
+
+export interface ApiStatus {
+    state: 'stopped' | 'starting' | 'running' | 'error';
+    address: string | null;
+    last_error: string | null;
+}
+
+interface SettingsError {
+    message: string;
+    fields?: { field: string; message: string }[];
+}
+
+const errorMessage = (error: unknown) => {
+    const settingsError = error as SettingsError;
+    return (
+        settingsError?.fields?.map((field) => field.message).join(', ') ||
+        settingsError?.message ||
+        String(error)
+    );
+};
 
 export const useApiState = () => {
     const [apiEnabled, setApiEnabled] = useState<boolean>(false);
     const [apiPort, setApiPort] = useState<number>(4800);
+    const [apiKey, setApiKey] = useState<string>('');
+    const [apiStatus, setApiStatus] = useState<ApiStatus | null>(null);
+    const [apiBindAddress, setApiBindAddress] = useState<string>('127.0.0.1');
+    const [apiAllowExternalBind, setApiAllowExternalBind] =
+        useState<boolean>(false);
+    const [apiSocketPath, setApiSocketPath] = useState<string>('');
+    const [bindError, setBindError] = useState<string | null>(null);
 
     const loadApiState = async () => {
         try {
@@ -11,6 +40,13 @@ export const useApiState = () => {
             const port = await invoke<number>('get_api_port');
             setApiEnabled(enabled);
             setApiPort(port);
+            setApiKey(await invoke<string>('get_api_key'));
+            setApiBindAddress(await invoke<string>('get_api_bind_address'));
+            setApiAllowExternalBind(
+                await invoke<boolean>('get_api_allow_external_bind')
+            );
+            setApiSocketPath(await invoke<string>('get_api_socket_path'));
+            setApiStatus(await invoke<ApiStatus>('get_api_status'));
         } catch (error) {
             console.error('Failed to load API state:', error);
         }
@@ -18,30 +54,36 @@ export const useApiState = () => {
 
     useEffect(() => {
         loadApiState();
+
+        const unlistenPromise = listen<ApiStatus>(
+            'api-status-changed',
+            (event) => {
+                setApiStatus(event.payload);
+            }
+        );
+
+        return () => {
+            unlistenPromise.then((unlisten) => unlisten());
+        };
     }, []);
 
+    useSettingsChanged(
+        [
+            'api_enabled',
+            'api_port',
+            'api_key',
+            'api_bind_address',
+            'api_allow_external_bind',
+            'api_socket_path',
+        ],
+        loadApiState
+    );
+
     const handleSetApiEnabled = async (enabled: boolean) => {
         try {
             setApiEnabled(enabled);
+            // The backend starts or stops the server to match the setting
             await invoke('set_api_enabled', { enabled });
-
-            if (enabled) {
-                // Start the HTTP API server immediately when enabled
-                try {
-                    await invoke('start_http_api_server');
-                } catch (error) {
-                    console.error('Failed to start HTTP API server:', error);
-                    // Revert the state on error
-                    setApiEnabled(false);
-                }
-            } else {
-                // Stop the HTTP API server when disabled
-                try {
-                    await invoke('stop_http_api_server');
-                } catch (error) {
-                    console.error('Failed to stop HTTP API server:', error);
-                }
-            }
         } catch (error) {
             console.error('Failed to set API enabled:', error);
             // Revert the state on error
@@ -53,27 +95,59 @@ export const useApiState = () => {
         if (port >= 1024 && port <= 65535) {
             try {
                 setApiPort(port);
+                // A running server is restarted on the new port
                 await invoke('set_api_port', { port });
-
-                if (apiEnabled) {
-                    try {
-                        await invoke('stop_http_api_server');
-                        await new Promise(resolve => setTimeout(resolve, 100));
-                        await invoke('start_http_api_server');
-                    } catch (error) {
-                        console.error('Failed to restart HTTP API server with new port:', error);
-                    }
-                }
             } catch (error) {
                 console.error('Failed to set API port:', error);
             }
         }
     };
 
+    // Rejected values are reported next to the controls, then reloaded
+    const updateBinding = async (
+        command: string,
+        args: Record<string, unknown>
+    ) => {
+        try {
+            await invoke(command, args);
+            setBindError(null);
+        } catch (error) {
+            setBindError(errorMessage(error));
+        }
+        await loadApiState();
+    };
+
+    const handleSetApiBindAddress = (address: string) =>
+        updateBinding('set_api_bind_address', { address });
+
+    const handleSetApiAllowExternalBind = (allow: boolean) =>
+        updateBinding('set_api_allow_external_bind', { allow });
+
+    const handleSetApiSocketPath = (path: string) =>
+        updateBinding('set_api_socket_path', { path });
+
+    const handleRegenerateApiKey = async () => {
+        try {
+            setApiKey(await invoke<string>('regenerate_api_key'));
+        } catch (error) {
+            console.error('Failed to regenerate API key:', error);
+        }
+    };
+
     return {
         setApiEnabled: handleSetApiEnabled,
         setApiPort: handleSetApiPort,
+        regenerateApiKey: handleRegenerateApiKey,
+        setApiBindAddress: handleSetApiBindAddress,
+        setApiAllowExternalBind: handleSetApiAllowExternalBind,
+        setApiSocketPath: handleSetApiSocketPath,
         apiEnabled,
         apiPort,
+        apiKey,
+        apiStatus,
+        apiBindAddress,
+        apiAllowExternalBind,
+        apiSocketPath,
+        bindError,
     };
 };
@@ -0,0 +1,90 @@
+import { SettingsUI } from '@/components/settings-ui';
+import { Typography } from '@/components/typography';
+import { Button } from '@/components/button';
+import {
+    Dialog,
+    DialogContent,
+    DialogDescription,
+    DialogFooter,
+    DialogHeader,
+    DialogTitle,
+} from '@/components/dialog';
+import { Download, Package, Upload } from 'lucide-react';
+import { useConfigBundle } from './hooks/use-config-bundle';
+
+export const ConfigBundleSettings = () => {
+    const { preview, exportBundle, previewBundle, applyImport, cancelImport } =
+        useConfigBundle();
+
+    return (
+        <>
+            <SettingsUI.Item>
+                <SettingsUI.Description>
+                    <Typography.Title className="flex items-center gap-2">
+                        <Package className="w-4 h-4 text-zinc-400" />
+                        Configuration
+                    </Typography.Title>
+                    <Typography.Paragraph>
+                        Share settings, profiles and dictionaries as a JSON or
+                        TOML file. The API key stays on this computer.
+                    </Typography.Paragraph>
+                </SettingsUI.Description>
+                <div className="flex items-center gap-2">
+                    <Button variant="outline" size="sm" onClick={exportBundle}>
+                        <Download />
+                        Export
+                    </Button>
+                    <Button variant="outline" size="sm" onClick={previewBundle}>
+                        <Upload />
+                        Import
+                    </Button>
+                </div>
+            </SettingsUI.Item>
+
+            <Dialog
+                open={preview !== null}
+                onOpenChange={(open) => !open && cancelImport()}
+            >
+                <DialogContent>
+                    <DialogHeader>
+                        <DialogTitle>Import configuration</DialogTitle>
+                        <DialogDescription>
+                            {preview?.errors.length
+                                ? 'This file cannot be imported:'
+                                : preview?.changes.length
+                                  ? 'These settings will change:'
+                                  : 'This file matches your current settings.'}
+                        </DialogDescription>
+                    </DialogHeader>
+                    <ul className="text-sm space-y-1 max-h-64 overflow-y-auto">
+                        {preview?.errors.map((error) => (
+                            <li key={error.field} className="text-red-400">
+                                <code>{error.field}</code>: {error.message}
+                            </li>
+                        ))}
+                        {!preview?.errors.length &&
+                            preview?.changes.map((change) => (
+                                <li key={change.field}>
+                                    <code>{change.field}</code>
+                                </li>
+                            ))}
+                    </ul>
+                    <DialogFooter>
+                        <Button variant="outline" onClick={cancelImport}>
+                            Cancel
+                        </Button>
+                        <Button
+                            onClick={applyImport}
+                            disabled={
+                                !preview?.changes.length ||
+                                preview.errors.length > 0
+                            }
+                        >
+                            Apply
+                        </Button>
+                    </DialogFooter>
+                </DialogContent>
+            </Dialog>
+        </>
+    );
+};
@@ -0,0 +1,80 @@
+import { invoke } from '@tauri-apps/api/core';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import { useState } from 'react';
+import { toast } from 'sonner';
+
+interface SettingsChange {
+    field: string;
+    old: unknown;
+    new: unknown;
+}
+
+interface FieldError {
+    field: string;
+    message: string;
+}
+
+export interface BundlePreview {
+    changes: SettingsChange[];
+    errors: FieldError[];
+}
+
+const filters = [
+    { name: 'Murmure configuration', extensions: ['json', 'toml'] },
+];
+
+const errorMessage = (error: unknown) =>
+    (error as { message?: string })?.message ?? String(error);
+
+export const useConfigBundle = () => {
+    const [pendingPath, setPendingPath] = useState<string | null>(null);
+    const [preview, setPreview] = useState<BundlePreview | null>(null);
+
+    const exportBundle = async () => {
+        const path = await save({
+            defaultPath: 'murmure-config.json',
+            filters,
+        });
+        if (!path) return;
+        try {
+            await invoke('export_config_bundle', { path });
+            toast.success('Configuration exported');
+        } catch (error) {
+            toast.error(errorMessage(error));
+        }
+    };
+
+    const previewBundle = async () => {
+        const path = await open({ multiple: false, filters });
+        if (typeof path !== 'string') return;
+        try {
+            setPreview(
+                await invoke<BundlePreview>('preview_config_bundle', { path })
+            );
+            setPendingPath(path);
+        } catch (error) {
+            toast.error(errorMessage(error));
+        }
+    };
+
+    const cancelImport = () => {
+        setPreview(null);
+        setPendingPath(null);
+    };
+
+    const applyImport = async () => {
+        if (!pendingPath) return;
+        try {
+            const changes = await invoke<SettingsChange[]>(
+                'import_config_bundle',
+                { path: pendingPath }
+            );
+            toast.success(`Configuration imported, ${changes.length} change(s)`);
+        } catch (error) {
+            toast.error(errorMessage(error));
+        }
+        cancelImport();
+    };
+
+    return { preview, exportBundle, previewBundle, applyImport, cancelImport };
+};
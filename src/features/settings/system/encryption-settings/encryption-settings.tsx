@@ -0,0 +1,99 @@
+import { useState } from 'react';
+import { SettingsUI } from '@/components/settings-ui';
+import { Typography } from '@/components/typography';
+import { Switch } from '@/components/switch';
+import { Input } from '@/components/input';
+import { Button } from '@/components/button';
+import { KeyRound, Lock, RefreshCw } from 'lucide-react';
+import { useEncryptionState } from './hooks/use-encryption-state';
+
+export const EncryptionSettings = () => {
+    const { status, error, enable, disable, unlock, rotate } =
+        useEncryptionState();
+    const [passphrase, setPassphrase] = useState('');
+
+    const locked = status?.enabled && !status.unlocked;
+
+    const handleToggle = async (enabled: boolean) => {
+        await (enabled ? enable(passphrase) : disable());
+        setPassphrase('');
+    };
+
+    return (
+        <>
+            <SettingsUI.Item>
+                <SettingsUI.Description>
+                    <Typography.Title className="flex items-center gap-2">
+                        <Lock className="w-4 h-4 text-zinc-400" />
+                        Encrypt local data
+                    </Typography.Title>
+                    <Typography.Paragraph className="space-y-2">
+                        <div>
+                            Encrypts settings, history and kept recordings on
+                            disk. Without a passphrase, the key is stored next
+                            to the data in keyring.json. With a passphrase, it
+                            must be entered after each start.
+                        </div>
+                        {error && (
+                            <div className="text-xs text-red-400">{error}</div>
+                        )}
+                    </Typography.Paragraph>
+                </SettingsUI.Description>
+                <Switch
+                    checked={status?.enabled ?? false}
+                    disabled={locked}
+                    onCheckedChange={handleToggle}
+                />
+            </SettingsUI.Item>
+            <SettingsUI.Separator />
+            <SettingsUI.Item>
+                <SettingsUI.Description>
+                    <Typography.Title>
+                        {locked ? 'Unlock' : 'Passphrase'}
+                    </Typography.Title>
+                    <Typography.Paragraph>
+                        {locked
+                            ? 'Your data is locked, enter the passphrase to use it.'
+                            : status?.enabled
+                              ? 'Rotate the key, optionally with a new passphrase. Leave it empty to keep the key in keyring.json.'
+                              : 'Optional, used when enabling encryption.'}
+                    </Typography.Paragraph>
+                </SettingsUI.Description>
+                <div className="flex items-center gap-2">
+                    <Input
+                        type="password"
+                        className="w-48"
+                        value={passphrase}
+                        onChange={(event) => setPassphrase(event.target.value)}
+                    />
+                    {locked && (
+                        <Button
+                            variant="outline"
+                            size="sm"
+                            onClick={async () => {
+                                await unlock(passphrase);
+                                setPassphrase('');
+                            }}
+                        >
+                            <KeyRound />
+                            Unlock
+                        </Button>
+                    )}
+                    {status?.enabled && !locked && (
+                        <Button
+                            variant="outline"
+                            size="sm"
+                            onClick={async () => {
+                                await rotate(passphrase);
+                                setPassphrase('');
+                            }}
+                        >
+                            <RefreshCw />
+                            Rotate key
+                        </Button>
+                    )}
+                </div>
+            </SettingsUI.Item>
+        </>
+    );
+};
@@ -0,0 +1,58 @@
+import { invoke } from '@tauri-apps/api/core';
+import { useState, useEffect } from 'react';
+
+export interface EncryptionStatus {
+    enabled: boolean;
+    source: 'keyfile' | 'passphrase' | null;
+    unlocked: boolean;
+}
+
+export const useEncryptionState = () => {
+    const [status, setStatus] = useState<EncryptionStatus | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    const loadStatus = async () => {
+        try {
+            setStatus(
+                await invoke<EncryptionStatus>('get_encryption_status')
+            );
+        } catch (error) {
+            console.error('Failed to load encryption status:', error);
+        }
+    };
+
+    useEffect(() => {
+        loadStatus();
+    }, []);
+
+    // Commands re-encrypt every file, refresh the status once they are done
+    const run = async (command: string, args?: Record<string, unknown>) => {
+        try {
+            setError(null);
+            await invoke(command, args);
+        } catch (error) {
+            console.error(`Failed to run ${command}:`, error);
+            setError(String(error));
+        }
+        await loadStatus();
+    };
+
+    const toPassphrase = (passphrase: string) =>
+        passphrase.length > 0 ? passphrase : null;
+
+    return {
+        status,
+        error,
+        enable: (passphrase: string) =>
+            run('enable_encryption', {
+                passphrase: toPassphrase(passphrase),
+            }),
+        disable: () => run('disable_encryption'),
+        unlock: (passphrase: string) =>
+            run('unlock_encryption', { passphrase }),
+        rotate: (passphrase: string) =>
+            run('rotate_encryption_key', {
+                passphrase: toPassphrase(passphrase),
+            }),
+    };
+};
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useState, useEffect } from 'react';
+import { useSettingsChanged } from '@/components/hooks/use-settings-changed';
 
 export const useOverlayState = () => {
     const [overlayMode, setOverlayMode] = useState<
@@ -9,7 +10,7 @@ export const useOverlayState = () => {
         'bottom'
     );
 
-    useEffect(() => {
+    const loadOverlayState = () => {
         invoke<string>('get_overlay_mode').then((m) => {
             if (m === 'hidden' || m === 'recording' || m === 'always')
                 setOverlayMode(m);
@@ -18,8 +19,14 @@ export const useOverlayState = () => {
         invoke<string>('get_overlay_position').then((p) => {
             if (p === 'top' || p === 'bottom') setOverlayPosition(p);
         });
+    };
+
+    useEffect(() => {
+        loadOverlayState();
     }, []);
 
+    useSettingsChanged(['overlay_mode', 'overlay_position'], loadOverlayState);
+
     return {
         setOverlayMode: (m: 'hidden' | 'recording' | 'always') => {
             setOverlayMode(m);
@@ -2,6 +2,8 @@ import { Typography } from '@/components/typography';
 import { SettingsUI } from '@/components/settings-ui';
 import { Page } from '@/components/page';
 import { APISettings } from './api-settings/api-settings';
+import { ConfigBundleSettings } from './config-bundle-settings/config-bundle-settings';
+import { EncryptionSettings } from './encryption-settings/encryption-settings';
 import { OverlaySettings } from './overlay-settings/overlay-settings';
 import { StartOnBootSettings } from './start-on-boot-settings/start-on-boot-settings';
 
@@ -24,6 +26,10 @@ export const System = () => {
                         <OverlaySettings />
                         <SettingsUI.Separator />
                         <APISettings />
+                        <SettingsUI.Separator />
+                        <EncryptionSettings />
+                        <SettingsUI.Separator />
+                        <ConfigBundleSettings />
                     </SettingsUI.Container>
                 </div>
             </div>
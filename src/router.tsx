@@ -10,6 +10,7 @@ import { About } from './features/about/about';
 import { Shortcuts } from './features/settings/shortcuts/shortcuts';
 import { CustomDictionary } from './features/settings/custom-dictionary/custom-dictionary';
 import { System } from './features/settings/system/system';
+import { Profiles } from './features/settings/profiles/profiles';
 
 const rootRoute = createRootRoute({
     component: () => <Layout />,
@@ -33,6 +34,12 @@ const settingsCustomDictionaryRoute = createRoute({
     component: CustomDictionary,
 });
 
+const settingsProfilesRoute = createRoute({
+    getParentRoute: () => rootRoute,
+    path: '/settings/profiles',
+    component: Profiles,
+});
+
 const settingsSystemRoute = createRoute({
     getParentRoute: () => rootRoute,
     path: '/settings/system',
@@ -56,6 +63,7 @@ const routeTree = rootRoute.addChildren([
     settingsIndexRoute,
     settingsShortcutsRoute,
     settingsCustomDictionaryRoute,
+    settingsProfilesRoute,
     settingsSystemRoute,
     aboutRoute,
 ]);